@@ -1,6 +1,10 @@
 use std::{env, fmt, fs, path};
+use std::collections::BTreeMap;
 use std::io::Write;
 
+#[path = "build/tzdb.rs"]
+mod tzdb;
+
 fn main() {
     // The total number of days in the year up to the current month, inclusive,
     // for common (non-leap) years and leap years.
@@ -60,6 +64,98 @@ fn main() {
     }
     with!(month_data);
     with!(day_of_the_week_data);
+
+    if env::var_os("CARGO_FEATURE_TZDB").is_some() {
+        write_tzdb(path.parent().unwrap())
+    }
+}
+
+/// Compile the vendored IANA time zone database, see `src/tzdb.rs`.
+fn write_tzdb(out_dir: &path::Path) {
+    for file in &["build.rs", "build/tzdb.rs", "tzdata/tzdata.zi"] {
+        println!("cargo:rerun-if-changed={}", file);
+    }
+    let database = tzdb::compile(&fs::read_to_string("tzdata/tzdata.zi").unwrap());
+
+    // All TZif data in one file, and where each zone or link is in it with its footer.
+    let mut data = Vec::new();
+    let mut zones = BTreeMap::new();
+    for (name, tzif, footer) in &database.zones {
+        let footer = match footer {
+            Some(footer) => format!("Some({})", footer.to_rust()),
+            None => "None".to_owned(),
+        };
+        zones.insert(name.clone(), (data.len(), data.len() + tzif.len(), footer));
+        data.extend_from_slice(tzif);
+    }
+    let mut links = BTreeMap::new();
+    for (link, target) in &database.links {
+        zones.insert(link.clone(), zones[target].clone());
+        links.insert(link.clone(), target.clone());
+    }
+    fs::write(out_dir.join("tzdb.bin"), data).unwrap();
+
+    // Parsed at compile time, so that conversions only read the data.
+    let mut file = fs::File::create(out_dir.join("tzdb.rs")).unwrap();
+    writeln!(file, "static ZONES: [(&str, Tzif<&[u8]>); {}] = [", zones.len()).unwrap();
+    for (name, (start, end, footer)) in &zones {
+        writeln!(file, "    ({:?}, compiled({}, {}, {})),", name, start, end, footer).unwrap();
+    }
+    writeln!(file, "];").unwrap();
+    let indices = zones.keys().enumerate().map(|(i, name)| (name, i)).collect::<BTreeMap<_, _>>();
+
+    // Group zones and links by module, for example `America::Argentina`.
+    let mut modules = BTreeMap::<Vec<String>, Vec<String>>::new();
+    for name in zones.keys() {
+        let mut path = name.split('/').map(identifier).collect::<Vec<_>>();
+        let item = path.pop().unwrap();
+        for i in 0..path.len() {
+            modules.entry(path[..i].to_vec()).or_default();
+        }
+        let item = match links.get(name) {
+            Some(target) => format!(
+                "pub use tzdb::{} as {};",
+                target.split('/').map(identifier).collect::<Vec<_>>().join("::"),
+                item
+            ),
+            None => format!("zone!({}, {:?}, {});", item, name, indices[name]),
+        };
+        modules.entry(path).or_default().push(item);
+    }
+    write_module(&mut file, &modules, &[]);
+}
+
+fn write_module(file: &mut fs::File, modules: &BTreeMap<Vec<String>, Vec<String>>,
+                path: &[String]) {
+    let indent = "    ".repeat(path.len());
+    for item in &modules[path] {
+        writeln!(file, "{}{}", indent, item).unwrap();
+    }
+    for submodule in modules.keys() {
+        if submodule.len() == path.len() + 1 && submodule.starts_with(path) {
+            let name = submodule.last().unwrap();
+            writeln!(file, "{}#[allow(non_snake_case)]", indent).unwrap();
+            writeln!(file, "{}pub mod {} {{", indent, name).unwrap();
+            write_module(file, modules, submodule);
+            writeln!(file, "{}}}", indent).unwrap();
+        }
+    }
+}
+
+/// `Port-au-Prince` to `Port_au_Prince`, `GMT+5` to `GMT_plus_5`, `GMT-5` to `GMT_minus_5`.
+fn identifier(name: &str) -> String {
+    let mut identifier = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        let before_digit = chars.peek().is_some_and(|c| c.is_ascii_digit());
+        match c {
+            '+' => identifier.push_str("_plus_"),
+            '-' if before_digit => identifier.push_str("_minus_"),
+            '-' => identifier.push('_'),
+            _ => identifier.push(c),
+        }
+    }
+    identifier
 }
 
 /// Wrap a string to format without quotes.
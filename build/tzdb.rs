@@ -0,0 +1,618 @@
+//! Compile the IANA time zone database from the `zic` input in `tzdata/tzdata.zi`
+//! into TZif data, for the `tzdb` Cargo feature.
+//!
+//! This implements the subset of `zic` used by that file.
+//! Transitions are listed explicitly up to 2037 (or later if rules require it),
+//! and a POSIX `TZ` string footer covers later instants when the final rules allow it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Transitions are listed explicitly up to this year, at least.
+const LAST_EXPLICIT_YEAR: i32 = 2037;
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+pub struct Database {
+    /// Zone name, TZif data, and the footer of that data, sorted by name
+    pub zones: Vec<(String, Vec<u8>, Option<Footer>)>,
+    /// Link name and target zone name, sorted by link name
+    pub links: Vec<(String, String)>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Suffix {
+    Wall,
+    Standard,
+    Universal,
+}
+
+#[derive(Copy, Clone)]
+enum Day {
+    Number(i32),
+    /// Weekday, 0 for Sunday
+    Last(i32),
+    OnOrAfter(i32, i32),
+    OnOrBefore(i32, i32),
+}
+
+struct Rule {
+    from: i32,
+    to: i32,
+    month: i32,
+    day: Day,
+    at: i64,
+    at_suffix: Suffix,
+    save: i64,
+    letter: String,
+}
+
+enum EraRules {
+    Fixed(i64),
+    Named(String),
+}
+
+struct Until {
+    year: i32,
+    month: i32,
+    day: Day,
+    time: i64,
+    suffix: Suffix,
+}
+
+struct Era {
+    offset: i64,
+    rules: EraRules,
+    format: String,
+    until: Option<Until>,
+}
+
+/// A POSIX `TZ` string for instants after the last explicit transition,
+/// kept in parts so that it can be written both in TZif data and as Rust code.
+pub struct Footer {
+    std_abbreviation: String,
+    /// Seconds ahead of UTC, the opposite of POSIX
+    std_offset: i64,
+    dst: Option<FooterDst>,
+}
+
+struct FooterDst {
+    abbreviation: String,
+    offset: i64,
+    start: FooterRule,
+    end: FooterRule,
+}
+
+/// `Jn` or `Mm.w.d`, and the local time in seconds
+struct FooterRule {
+    date: FooterDate,
+    time: i64,
+}
+
+enum FooterDate {
+    Julian(i32),
+    MonthWeekDay(i32, i32, i32),
+}
+
+#[derive(Clone, PartialEq)]
+struct LocalTimeType {
+    offset: i64,
+    is_dst: bool,
+    abbreviation: String,
+}
+
+pub fn compile(source: &str) -> Database {
+    let mut rules = BTreeMap::<String, Vec<Rule>>::new();
+    let mut zones = Vec::<(String, Vec<Era>)>::new();
+    let mut links = Vec::new();
+    for line in source.lines() {
+        let line = line.split('#').next().unwrap();
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        match fields.first() {
+            None => {}
+            Some(&"R") => {
+                let from = fields[2].parse().unwrap();
+                let (at, at_suffix) = time(fields[7]);
+                rules.entry(fields[1].to_owned()).or_default().push(Rule {
+                    from,
+                    to: match fields[3] {
+                        "o" => from,
+                        "ma" => i32::MAX,
+                        year => year.parse().unwrap(),
+                    },
+                    month: month(fields[5]),
+                    day: day(fields[6]),
+                    at,
+                    at_suffix,
+                    save: time(fields[8]).0,
+                    letter: if fields[9] == "-" { String::new() } else { fields[9].to_owned() },
+                })
+            }
+            Some(&"Z") => zones.push((fields[1].to_owned(), vec![era(&fields[2..])])),
+            Some(&"L") => links.push((fields[2].to_owned(), fields[1].to_owned())),
+            Some(_) => zones.last_mut().unwrap().1.push(era(&fields)),
+        }
+    }
+
+    let mut zones = zones.iter().map(|(name, eras)| {
+        let (tzif, footer) = compile_zone(eras, &rules);
+        (name.clone(), tzif, footer)
+    }).collect::<Vec<_>>();
+    zones.sort_by(|a, b| a.0.cmp(&b.0));
+    links.sort();
+    Database { zones, links }
+}
+
+fn era(fields: &[&str]) -> Era {
+    let rules = match fields[1] {
+        "-" => EraRules::Fixed(0),
+        s if s.starts_with(|c: char| c.is_ascii_digit() || c == '-') => EraRules::Fixed(time(s).0),
+        s => EraRules::Named(s.to_owned()),
+    };
+    let until = if fields.len() > 3 {
+        let (time, suffix) = fields.get(6).map_or((0, Suffix::Wall), |s| time(s));
+        Some(Until {
+            year: fields[3].parse().unwrap(),
+            month: fields.get(4).map_or(1, |s| month(s)),
+            day: fields.get(5).map_or(Day::Number(1), |s| day(s)),
+            time,
+            suffix,
+        })
+    } else {
+        None
+    };
+    Era { offset: time(fields[0]).0, rules, format: fields[2].to_owned(), until }
+}
+
+/// Match a possibly abbreviated name, case-insensitively.
+fn lookup(names: &[&str], s: &str) -> i32 {
+    let matches = names.iter().enumerate().filter(|&(_, name)| {
+        name.len() >= s.len() && name[..s.len()].eq_ignore_ascii_case(s)
+    }).collect::<Vec<_>>();
+    assert_eq!(matches.len(), 1, "ambiguous or unknown name {:?}", s);
+    matches[0].0 as i32
+}
+
+const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July",
+                            "August", "September", "October", "November", "December"];
+
+/// 1 to 12
+fn month(s: &str) -> i32 {
+    lookup(&MONTHS, s) + 1
+}
+
+/// 0 for Sunday
+fn weekday(s: &str) -> i32 {
+    lookup(&["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"], s)
+}
+
+fn day(s: &str) -> Day {
+    if let Some(weekday_name) = s.strip_prefix("last") {
+        Day::Last(weekday(weekday_name))
+    } else if let Some(i) = s.find(">=") {
+        Day::OnOrAfter(weekday(&s[..i]), s[i + 2..].parse().unwrap())
+    } else if let Some(i) = s.find("<=") {
+        Day::OnOrBefore(weekday(&s[..i]), s[i + 2..].parse().unwrap())
+    } else {
+        Day::Number(s.parse().unwrap())
+    }
+}
+
+/// `[-]h[:mm[:ss]]` with an optional suffix, in seconds.
+fn time(s: &str) -> (i64, Suffix) {
+    let (s, suffix) = match s.as_bytes()[s.len() - 1] {
+        b's' => (&s[..s.len() - 1], Suffix::Standard),
+        b'u' | b'g' | b'z' => (&s[..s.len() - 1], Suffix::Universal),
+        b'w' | b'd' => (&s[..s.len() - 1], Suffix::Wall),
+        _ => (s, Suffix::Wall),
+    };
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(s) => (-1, s),
+        None => (1, s),
+    };
+    let mut seconds = 0;
+    let mut unit = 3600;
+    for part in s.split(':') {
+        seconds += part.parse::<i64>().unwrap() * unit;
+        unit /= 60;
+    }
+    (sign * seconds, suffix)
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+/// `day` can be out of the range of the month.
+fn days_since_unix(year: i32, month: i32, day: i32) -> i64 {
+    let (year, month) = (i64::from(year), i64::from(month));
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468 + i64::from(day) - 1
+}
+
+fn month_length(year: i32, month: i32) -> i32 {
+    let next = if month == 12 { days_since_unix(year + 1, 1, 1) } else {
+        days_since_unix(year, month + 1, 1)
+    };
+    (next - days_since_unix(year, month, 1)) as i32
+}
+
+/// 0 for Sunday
+fn weekday_of(days_since_unix: i64) -> i32 {
+    (days_since_unix + 4).rem_euclid(7) as i32
+}
+
+/// Days since 1970-01-01
+fn resolve_day(year: i32, month: i32, day: Day) -> i64 {
+    let first = days_since_unix(year, month, 1);
+    let day = match day {
+        Day::Number(day) => day,
+        Day::Last(weekday) => {
+            let last = month_length(year, month);
+            last - (weekday_of(first + i64::from(last) - 1) - weekday).rem_euclid(7)
+        }
+        Day::OnOrAfter(weekday, day) => {
+            day + (weekday - weekday_of(first + i64::from(day) - 1)).rem_euclid(7)
+        }
+        Day::OnOrBefore(weekday, day) => {
+            day - (weekday_of(first + i64::from(day) - 1) - weekday).rem_euclid(7)
+        }
+    };
+    first + i64::from(day) - 1
+}
+
+/// Seconds to subtract from a local time with `suffix` to get UTC.
+fn to_utc(suffix: Suffix, offset: i64, save: i64) -> i64 {
+    match suffix {
+        Suffix::Wall => offset + save,
+        Suffix::Standard => offset,
+        Suffix::Universal => 0,
+    }
+}
+
+fn abbreviation(format: &str, offset: i64, save: i64, letter: &str) -> String {
+    if let Some(i) = format.find('/') {
+        return if save == 0 { &format[..i] } else { &format[i + 1..] }.to_owned()
+    }
+    let total = offset + save;
+    let sign = if total < 0 { '-' } else { '+' };
+    let total = total.abs();
+    let mut numeric = format!("{}{:02}", sign, total / 3600);
+    if total % 3600 != 0 {
+        numeric += &format!("{:02}", total / 60 % 60);
+    }
+    if total % 60 != 0 {
+        numeric += &format!("{:02}", total % 60);
+    }
+    format.replace("%s", letter).replace("%z", &numeric)
+}
+
+/// Transitions of one zone, as instants and the local time type from then on.
+struct Builder {
+    initial: Option<LocalTimeType>,
+    transitions: Vec<(i64, LocalTimeType)>,
+}
+
+impl Builder {
+    fn push(&mut self, t: Option<i64>, local_time_type: LocalTimeType) {
+        let t = match t {
+            Some(t) => t,
+            None => return self.initial = Some(local_time_type),
+        };
+        if let Some(&(last, _)) = self.transitions.last() {
+            if last >= t {
+                self.transitions.pop();
+            }
+        }
+        let current = self.transitions.last().map(|(_, t)| t).or(self.initial.as_ref());
+        if current != Some(&local_time_type) {
+            self.transitions.push((t, local_time_type))
+        }
+    }
+}
+
+fn compile_zone(eras: &[Era], rules: &BTreeMap<String, Vec<Rule>>)
+                -> (Vec<u8>, Option<Footer>) {
+    let mut builder = Builder { initial: None, transitions: Vec::new() };
+    let mut start = None;
+    // Standard offset and saving at the end of the previous era
+    let mut previous = (0, 0);
+    let mut footer = None;
+    for era in eras {
+        let offset = era.offset;
+        let until = |save| era.until.as_ref().map(|u| {
+            resolve_day(u.year, u.month, u.day) * SECONDS_PER_DAY + u.time
+                - to_utc(u.suffix, offset, save)
+        });
+        let named = match era.rules {
+            EraRules::Fixed(save) => {
+                builder.push(start, LocalTimeType {
+                    offset: offset + save,
+                    is_dst: save != 0,
+                    abbreviation: abbreviation(&era.format, offset, save, ""),
+                });
+                if era.until.is_none() {
+                    footer = Some(Some(posix_std(&abbreviation(&era.format, offset, save, ""),
+                                                 offset + save)));
+                }
+                start = until(save);
+                previous = (offset, save);
+                continue
+            }
+            EraRules::Named(ref name) => &rules[name],
+        };
+
+        // The rule in effect at the start of the era may be from any earlier year.
+        let first_year = named.iter().map(|r| r.from).min().unwrap();
+        let last_year = match era.until {
+            Some(ref until) => until.year + 1,
+            None => named.iter().map(|r| if r.to == i32::MAX { r.from } else { r.to })
+                         .max().unwrap().max(LAST_EXPLICIT_YEAR),
+        };
+        let mut candidates = Vec::new();
+        for year in first_year..=last_year {
+            for rule in named {
+                if rule.from <= year && year <= rule.to {
+                    let local = resolve_day(year, rule.month, rule.day) * SECONDS_PER_DAY + rule.at;
+                    candidates.push((local, rule))
+                }
+            }
+        }
+        candidates.sort_by_key(|&(local, _)| local);
+
+        // Before the first rule transition, use standard time
+        // with the letter of the first rule that has no saving.
+        let standard_letter = named.iter().find(|r| r.save == 0).map_or("", |r| r.letter.as_str());
+        let mut save = 0;
+        let mut letter = standard_letter;
+        let local_time_type = |save, letter| LocalTimeType {
+            offset: offset + save,
+            is_dst: save != 0,
+            abbreviation: abbreviation(&era.format, offset, save, letter),
+        };
+        let mut started = false;
+        for &(local, rule) in &candidates {
+            let t = local - to_utc(rule.at_suffix, offset, save);
+            if let Some(until) = until(save) {
+                if t >= until {
+                    break
+                }
+            }
+            // Like zic, a rule is in effect from the start of the era if its time
+            // is not after the start in the local time of the previous era.
+            let t_previous = local - to_utc(rule.at_suffix, previous.0, previous.1);
+            if start.is_some_and(|start| t_previous <= start || t <= start) {
+                save = rule.save;
+                letter = &rule.letter;
+                continue
+            }
+            if !started {
+                builder.push(start, local_time_type(save, letter));
+                started = true;
+            }
+            save = rule.save;
+            letter = &rule.letter;
+            builder.push(Some(t), local_time_type(save, letter));
+        }
+        if !started {
+            builder.push(start, local_time_type(save, letter));
+        }
+        if era.until.is_none() {
+            footer = Some(posix_rules(era, named, save, letter));
+        }
+        start = until(save);
+        previous = (offset, save);
+    }
+    let footer = footer.and_then(|f| f);
+    (tzif(&builder, footer.as_ref()), footer)
+}
+
+fn posix_name(abbreviation: &str) -> String {
+    if abbreviation.len() >= 3 && abbreviation.bytes().all(|b| b.is_ascii_alphabetic()) {
+        abbreviation.to_owned()
+    } else {
+        format!("<{}>", abbreviation)
+    }
+}
+
+/// `[-]h[:mm[:ss]]`
+fn posix_time(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    let mut s = format!("{}{}", sign, seconds / 3600);
+    if seconds % 3600 != 0 {
+        s += &format!(":{:02}", seconds / 60 % 60);
+    }
+    if seconds % 60 != 0 {
+        s += &format!(":{:02}", seconds % 60);
+    }
+    s
+}
+
+fn posix_std(abbreviation: &str, offset: i64) -> Footer {
+    Footer { std_abbreviation: abbreviation.to_owned(), std_offset: offset, dst: None }
+}
+
+/// A footer for the final rules of a zone, if they can be expressed as a POSIX `TZ` string.
+/// `save` and `letter` are in effect after the last explicit transition.
+fn posix_rules(era: &Era, rules: &[Rule], save: i64, letter: &str) -> Option<Footer> {
+    let ongoing = rules.iter().filter(|r| r.to == i32::MAX).collect::<Vec<_>>();
+    let (dst, std) = match ongoing[..] {
+        [a, b] if a.save != 0 && b.save == 0 => (a, b),
+        [a, b] if a.save == 0 && b.save != 0 => (b, a),
+        _ if ongoing.iter().all(|r| r.save == save) => {
+            let abbreviation = abbreviation(&era.format, era.offset, save, letter);
+            return Some(posix_std(&abbreviation, era.offset + save))
+        }
+        _ => return None,
+    };
+    let mut footer = posix_std(&abbreviation(&era.format, era.offset, 0, &std.letter), era.offset);
+    footer.dst = Some(FooterDst {
+        abbreviation: abbreviation(&era.format, era.offset, dst.save, &dst.letter),
+        offset: era.offset + dst.save,
+        start: posix_rule(dst, era.offset, 0)?,
+        end: posix_rule(std, era.offset, dst.save)?,
+    });
+    Some(footer)
+}
+
+/// `Mm.w.d[/time]` or `Jn[/time]` in the local time in effect before the transition.
+fn posix_rule(rule: &Rule, offset: i64, save_before: i64) -> Option<FooterRule> {
+    let mut time = rule.at + to_utc(Suffix::Wall, offset, save_before)
+                 - to_utc(rule.at_suffix, offset, save_before);
+    let date = match rule.day {
+        Day::Number(day) => {
+            if rule.month == 2 && day == 29 {
+                return None
+            }
+            // Day of the year in a common year
+            let n = days_since_unix(2001, rule.month, day) - days_since_unix(2001, 1, 1) + 1;
+            FooterDate::Julian(n as i32)
+        }
+        Day::Last(weekday) => FooterDate::MonthWeekDay(rule.month, 5, weekday),
+        Day::OnOrAfter(weekday, day) | Day::OnOrBefore(weekday, day) => {
+            let day = match rule.day {
+                Day::OnOrBefore(..) => day - 6,
+                _ => day,
+            };
+            // The first `weekday` on or after `day` is `shift` days after
+            // the first `weekday - shift` on or after the first day of that week.
+            let week = (day - 1) / 7 + 1;
+            let shift = (day - 1) % 7;
+            if day < 1 || week > 4 {
+                return None
+            }
+            time += i64::from(shift) * SECONDS_PER_DAY;
+            FooterDate::MonthWeekDay(rule.month, week, (weekday - shift).rem_euclid(7))
+        }
+    };
+    Some(FooterRule { date, time })
+}
+
+impl fmt::Display for Footer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // POSIX offsets are positive west of Greenwich.
+        write!(f, "{}{}", posix_name(&self.std_abbreviation), posix_time(-self.std_offset))?;
+        if let Some(ref dst) = self.dst {
+            write!(f, "{}", posix_name(&dst.abbreviation))?;
+            if dst.offset != self.std_offset + 3600 {
+                write!(f, "{}", posix_time(-dst.offset))?;
+            }
+            write!(f, ",{},{}", dst.start, dst.end)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FooterRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.date {
+            FooterDate::Julian(n) => write!(f, "J{}", n)?,
+            FooterDate::MonthWeekDay(month, week, day) => {
+                write!(f, "M{}.{}.{}", month, week, day)?
+            }
+        }
+        if self.time != 7200 {
+            write!(f, "/{}", posix_time(self.time))?;
+        }
+        Ok(())
+    }
+}
+
+impl Footer {
+    /// An expression for the same `PosixTz` as parsing this footer, see `src/posix_tz.rs`.
+    pub fn to_rust(&self) -> String {
+        let dst = match self.dst {
+            Some(ref dst) => format!("Some(::posix_tz::compiled_dst({:?}, {}, {}, {}))",
+                                     dst.abbreviation, dst.offset,
+                                     dst.start.to_rust(), dst.end.to_rust()),
+            None => "None".to_owned(),
+        };
+        format!("::posix_tz::compiled({:?}, {}, {})", self.std_abbreviation, self.std_offset, dst)
+    }
+
+    /// POSIX `TZ` strings with transition times outside of 0 to 24 hours need TZif version 3.
+    fn needs_version_3(&self) -> bool {
+        self.dst.as_ref().is_some_and(|dst| {
+            [&dst.start, &dst.end].iter().any(|rule| !(0..=24 * 3600).contains(&rule.time))
+        })
+    }
+}
+
+impl FooterRule {
+    fn to_rust(&self) -> String {
+        let date = match self.date {
+            FooterDate::Julian(n) => {
+                format!("::posix_tz::RuleDate::OneBasedSkippingLeapDay({})", n)
+            }
+            FooterDate::MonthWeekDay(month, week, day) => format!(
+                "::posix_tz::RuleDate::MonthWeekDay {{ month: ::Month::{}, week: {}, day: {} }}",
+                MONTHS[month as usize - 1], week, day
+            ),
+        };
+        format!("::posix_tz::compiled_rule({}, {})", date, self.time)
+    }
+}
+
+fn tzif(builder: &Builder, footer: Option<&Footer>) -> Vec<u8> {
+    let mut types = vec![builder.initial.clone().unwrap()];
+    let mut transitions = Vec::new();
+    for (t, local_time_type) in &builder.transitions {
+        let index = match types.iter().position(|x| x == local_time_type) {
+            Some(index) => index,
+            None => {
+                types.push(local_time_type.clone());
+                types.len() - 1
+            }
+        };
+        transitions.push((*t, index as u8))
+    }
+    let mut chars = Vec::<u8>::new();
+    let mut abbreviation_indices = Vec::new();
+    for local_time_type in &types {
+        let abbreviation = local_time_type.abbreviation.as_bytes();
+        let index = chars.windows(abbreviation.len() + 1)
+                         .position(|w| &w[..abbreviation.len()] == abbreviation && w[abbreviation.len()] == 0)
+                         .unwrap_or_else(|| {
+                             chars.extend_from_slice(abbreviation);
+                             chars.push(0);
+                             chars.len() - abbreviation.len() - 1
+                         });
+        abbreviation_indices.push(index as u8)
+    }
+    let version = if footer.is_some_and(Footer::needs_version_3) { b'3' } else { b'2' };
+
+    let mut data = Vec::new();
+    let header = |data: &mut Vec<u8>, counts: [usize; 6]| {
+        data.extend_from_slice(b"TZif");
+        data.push(version);
+        data.extend_from_slice(&[0; 15]);
+        for &count in &counts {
+            data.extend_from_slice(&(count as u32).to_be_bytes())
+        }
+    };
+    // Version 1 data is superseded by version 2+ data: only a placeholder local time type.
+    header(&mut data, [0, 0, 0, 0, 1, 1]);
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0]);
+
+    header(&mut data, [0, 0, 0, transitions.len(), types.len(), chars.len()]);
+    for &(t, _) in &transitions {
+        data.extend_from_slice(&t.to_be_bytes())
+    }
+    for &(_, index) in &transitions {
+        data.push(index)
+    }
+    for (local_time_type, &index) in types.iter().zip(&abbreviation_indices) {
+        data.extend_from_slice(&(local_time_type.offset as i32).to_be_bytes());
+        data.push(local_time_type.is_dst as u8);
+        data.push(index);
+    }
+    data.extend_from_slice(&chars);
+    data.push(b'\n');
+    if let Some(footer) = footer {
+        data.extend_from_slice(footer.to_string().as_bytes());
+    }
+    data.push(b'\n');
+    data
+}
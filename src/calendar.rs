@@ -0,0 +1,78 @@
+//! Calendars that can be converted to each other through a common day number.
+
+use core::fmt;
+use super::{NaiveDate, Month, YearKind, InvalidDateTime};
+
+/// A calendar: its kinds of years, its month lengths,
+/// and conversions of its dates to and from a day number shared by all calendars.
+///
+/// ```rust
+/// use gregor::{Calendar, Gregorian, Julian, JulianDate, NaiveDate};
+/// use gregor::Month::*;
+/// let d = Gregorian.date(1918, February, 14).unwrap();
+/// assert_eq!(Gregorian.convert(&d, &Julian), JulianDate::new(1918, February, 1));
+/// assert_eq!(Julian.month_length(1900, February), 29);
+/// assert_eq!(Gregorian.month_length(1900, February), 28);
+/// ```
+pub trait Calendar {
+    /// A date in this calendar.
+    type Date: Copy + fmt::Debug;
+
+    /// The months of a year in this calendar.
+    type Month: Copy + fmt::Debug;
+
+    /// Kinds of years that have different lengths.
+    type YearKind: Copy + fmt::Debug;
+
+    fn year_kind(&self, year: i32) -> Self::YearKind;
+
+    /// The number of days in this month and year.
+    fn month_length(&self, year: i32, month: Self::Month) -> u8;
+
+    /// The date with these fields.
+    ///
+    /// Return an error if the day does not exist in this month and year.
+    fn date(&self, year: i32, month: Self::Month, day: u8)
+            -> Result<Self::Date, InvalidDateTime>;
+
+    /// Day number where 1970-01-01 in the Gregorian calendar is day 0.
+    fn days_since_unix(&self, date: &Self::Date) -> i32;
+
+    /// The inverse of `days_since_unix`.
+    fn date_from_days_since_unix(&self, days: i32) -> Self::Date;
+
+    /// The date on the same day in another calendar.
+    fn convert<C: Calendar>(&self, date: &Self::Date, calendar: &C) -> C::Date {
+        calendar.date_from_days_since_unix(self.days_since_unix(date))
+    }
+}
+
+/// The proleptic Gregorian calendar, as used by `NaiveDate`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct Gregorian;
+
+impl Calendar for Gregorian {
+    type Date = NaiveDate;
+    type Month = Month;
+    type YearKind = YearKind;
+
+    fn year_kind(&self, year: i32) -> YearKind {
+        YearKind::from(year)
+    }
+
+    fn month_length(&self, year: i32, month: Month) -> u8 {
+        month.length(YearKind::from(year))
+    }
+
+    fn date(&self, year: i32, month: Month, day: u8) -> Result<NaiveDate, InvalidDateTime> {
+        NaiveDate::try_new(year, month, day)
+    }
+
+    fn days_since_unix(&self, date: &NaiveDate) -> i32 {
+        date.days_since_unix()
+    }
+
+    fn date_from_days_since_unix(&self, days: i32) -> NaiveDate {
+        NaiveDate::from_days_since_unix(days)
+    }
+}
@@ -0,0 +1,194 @@
+use num::{div_floor, positive_rem};
+use super::{NaiveDateTime, NaiveDate, DateTime, TimeZone, UnambiguousTimeZone, Utc, Month,
+            ArithmeticError};
+
+/// What to do when adding months or years to a date gives a day that does not exist
+/// in the resulting month, such as adding one month to January 31st.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DayOverflow {
+    /// Use the last day of the month instead.
+    /// January 31st plus one month is February 28th, or February 29th in leap years.
+    Clamp,
+
+    /// Count the extra days into the next month.
+    /// January 31st plus one month is March 3rd, or March 2nd in leap years.
+    RollOver,
+
+    /// Return `Err(ArithmeticError::DayOutOfRange)`.
+    Reject,
+}
+
+impl NaiveDateTime {
+    /// Change the year and month by this many months (which can be negative),
+    /// keeping the day of the month and the time of day.
+    ///
+    /// ```rust
+    /// use gregor::{NaiveDateTime, DayOverflow};
+    /// use gregor::Month::*;
+    /// let d = NaiveDateTime::new(2016, January, 31, 12, 0, 0);
+    /// assert_eq!(d.add_months(1, DayOverflow::Clamp),
+    ///            Ok(NaiveDateTime::new(2016, February, 29, 12, 0, 0)));
+    /// assert_eq!(d.add_months(-2, DayOverflow::Clamp),
+    ///            Ok(NaiveDateTime::new(2015, November, 30, 12, 0, 0)));
+    /// ```
+    pub fn add_months(&self, months: i32, day_overflow: DayOverflow)
+                      -> Result<Self, ArithmeticError> {
+        self.add_i64_months(i64::from(months), day_overflow)
+    }
+
+    fn add_i64_months(&self, months: i64, day_overflow: DayOverflow)
+                      -> Result<Self, ArithmeticError> {
+        let months_since_y0 = months_since_y0(self) + months;
+        let year = div_floor(months_since_y0, 12);
+        if year < i64::from(i32::MIN) || year > i64::from(i32::MAX) {
+            return Err(ArithmeticError::Overflow)
+        }
+        let year = year as i32;
+        let month = Month::from_number(positive_rem(months_since_y0, 12) as u8 + 1).unwrap();
+        let length = month.length(year.into());
+        let (month, day) = if self.day <= length {
+            (month, self.day)
+        } else {
+            match day_overflow {
+                DayOverflow::Clamp => (month, length),
+                // December has 31 days, so this never rolls over into the next year.
+                DayOverflow::RollOver => (Month::from_number(month.to_number() + 1).unwrap(),
+                                          self.day - length),
+                DayOverflow::Reject => return Err(ArithmeticError::DayOutOfRange),
+            }
+        };
+        Ok(NaiveDateTime { year, month, day, .. *self })
+    }
+
+    /// Change the year by this many years (which can be negative),
+    /// keeping the month, day of the month and the time of day.
+    ///
+    /// `day_overflow` only matters for February 29th.
+    pub fn add_years(&self, years: i32, day_overflow: DayOverflow)
+                     -> Result<Self, ArithmeticError> {
+        self.add_i64_months(i64::from(years) * 12, day_overflow)
+    }
+}
+
+/// The difference between two dates and times, in calendar units.
+///
+/// All fields have the same sign: positive (or zero) if the end is after the start,
+/// negative (or zero) otherwise.
+/// Adding `years` and `months` to the start with `add_months` and `DayOverflow::Clamp`,
+/// then the remaining days, hours, minutes, seconds and nanoseconds as exact amounts of time,
+/// gives the end.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct CalendarDifference {
+    pub years: i64,
+    /// Between -11 and 11
+    pub months: i64,
+    /// Between -30 and 30
+    pub days: i64,
+    /// Between -23 and 23
+    pub hours: i64,
+    /// Between -59 and 59
+    pub minutes: i64,
+    /// Between -59 and 59
+    pub seconds: i64,
+    /// Between -999_999_999 and 999_999_999
+    pub nanoseconds: i64,
+}
+
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+const NANOSECONDS_PER_DAY: i128 = 86400 * NANOSECONDS_PER_SECOND;
+
+fn months_since_y0(d: &NaiveDateTime) -> i64 {
+    i64::from(d.year) * 12 + i64::from(d.month.to_number() - 1)
+}
+
+/// Nanoseconds from `earlier` to `later`, ignoring time zones.
+fn nanoseconds_between(later: &NaiveDateTime, earlier: &NaiveDateTime) -> i128 {
+    let nanoseconds = |d: &NaiveDateTime| {
+        let t = Utc.to_unambiguous_timestamp_nanos(d);
+        i128::from(t.seconds) * NANOSECONDS_PER_SECOND + i128::from(t.nanoseconds)
+    };
+    nanoseconds(later) - nanoseconds(earlier)
+}
+
+impl NaiveDateTime {
+    /// The number of complete 24 hour days from `earlier` to `self`, ignoring time zones.
+    /// Negative if `earlier` is in fact later.
+    pub fn days_since(&self, earlier: &NaiveDateTime) -> i64 {
+        (nanoseconds_between(self, earlier) / NANOSECONDS_PER_DAY) as i64
+    }
+
+    /// How many years, months, days, etc. from `earlier` to `self`, ignoring time zones.
+    /// See `CalendarDifference`.
+    ///
+    /// ```rust
+    /// use gregor::{NaiveDateTime, CalendarDifference};
+    /// use gregor::Month::*;
+    /// let birth = NaiveDateTime::new(1990, August, 31, 12, 0, 0);
+    /// let now = NaiveDateTime::new(2016, July, 16, 20, 58, 46);
+    /// assert_eq!(now.calendar_difference_since(&birth), CalendarDifference {
+    ///     years: 25, months: 10, days: 16, hours: 8, minutes: 58, seconds: 46, nanoseconds: 0,
+    /// });
+    /// ```
+    pub fn calendar_difference_since(&self, earlier: &NaiveDateTime) -> CalendarDifference {
+        let sign = if self >= earlier { 1 } else { -1 };
+        // Start with an estimate that may overshoot by one month, then step back.
+        let mut months = months_since_y0(self) - months_since_y0(earlier);
+        let intermediate = loop {
+            let intermediate = earlier.add_i64_months(months, DayOverflow::Clamp).unwrap();
+            if (sign > 0 && intermediate > *self) || (sign < 0 && intermediate < *self) {
+                months -= sign
+            } else {
+                break intermediate
+            }
+        };
+        let remaining = nanoseconds_between(self, &intermediate);
+        let component = |unit: i128, range: i128| ((remaining / unit) % range) as i64;
+        CalendarDifference {
+            years: months / 12,
+            months: months % 12,
+            days: (remaining / NANOSECONDS_PER_DAY) as i64,
+            hours: component(3600 * NANOSECONDS_PER_SECOND, 24),
+            minutes: component(60 * NANOSECONDS_PER_SECOND, 60),
+            seconds: component(NANOSECONDS_PER_SECOND, 60),
+            nanoseconds: component(1, NANOSECONDS_PER_SECOND),
+        }
+    }
+}
+
+impl NaiveDate {
+    /// The number of days from `earlier` to `self`.
+    /// Negative if `earlier` is in fact later.
+    pub fn days_since(&self, earlier: &NaiveDate) -> i64 {
+        i64::from(self.days_since_unix()) - i64::from(earlier.days_since_unix())
+    }
+
+    /// How many years, months and days from `earlier` to `self`.
+    /// The time fields of the result are zero.
+    /// See `CalendarDifference`.
+    pub fn calendar_difference_since(&self, earlier: &NaiveDate) -> CalendarDifference {
+        NaiveDateTime::from(*self).calendar_difference_since(&NaiveDateTime::from(*earlier))
+    }
+}
+
+impl<Tz: TimeZone + Clone> DateTime<Tz> {
+    /// See `NaiveDateTime::add_months`.
+    ///
+    /// The time zone is unchanged.
+    /// The resulting local time may not exist or may be ambiguous in this time zone.
+    pub fn add_months(&self, months: i32, day_overflow: DayOverflow)
+                      -> Result<Self, ArithmeticError> {
+        Ok(DateTime {
+            naive: self.naive.add_months(months, day_overflow)?,
+            time_zone: self.time_zone.clone(),
+        })
+    }
+
+    /// See `NaiveDateTime::add_years`.
+    pub fn add_years(&self, years: i32, day_overflow: DayOverflow)
+                     -> Result<Self, ArithmeticError> {
+        Ok(DateTime {
+            naive: self.naive.add_years(years, day_overflow)?,
+            time_zone: self.time_zone.clone(),
+        })
+    }
+}
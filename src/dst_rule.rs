@@ -0,0 +1,252 @@
+//! Time zones with daylight saving time that starts and ends every year
+//! on a given day of the week of a given month.
+
+use super::{NaiveDate, UnixTimestamp, TimeZone, DaylightSaving, Utc, FixedOffsetFromUtc, Month,
+            DayOfTheWeek, YearKind, InvalidDateTime};
+use num::positive_rem;
+use time_zones::yearly_dst_change;
+use Month::*;
+use DayOfTheWeek::*;
+
+/// A time zone with daylight saving time every year, defined by two `DstTransition` rules.
+///
+/// DST may span New Year in the southern hemisphere, when it ends earlier in the year than it starts.
+///
+/// Constants are provided for some regions.
+/// Other offsets with the same rules can be used with the struct update syntax:
+///
+/// ```rust
+/// use gregor::{DstRule, FixedOffsetFromUtc, DateTime, UnixTimestamp};
+/// let alaska = DstRule {
+///     standard_offset: FixedOffsetFromUtc::from_hours_and_minutes(-9, 0),
+///     dst_offset: FixedOffsetFromUtc::from_hours_and_minutes(-8, 0),
+///     ..DstRule::NORTH_AMERICA_EASTERN
+/// };
+/// assert_eq!(DateTime::from_timestamp(UnixTimestamp(1468702726), alaska).hour(), 12);
+/// ```
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct DstRule {
+    pub standard_offset: FixedOffsetFromUtc,
+    pub dst_offset: FixedOffsetFromUtc,
+    pub start: DstTransition,
+    pub end: DstTransition,
+}
+
+/// When daylight saving time starts or ends in a given year.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct DstTransition {
+    pub month: Month,
+    pub day: TransitionDay,
+    /// Seconds since midnight, possibly negative or more than 24 hours.
+    pub time: i32,
+    pub clock: TransitionClock,
+}
+
+/// Which day of the month a transition happens.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum TransitionDay {
+    /// The given day of the week in the given week (1 to 4) of the month,
+    /// for example `Nth(2, Sunday)` for the second Sunday.
+    ///
+    /// Other weeks are not checked: with 0, or with 5 in a month that has only four
+    /// of that day of the week, the transition silently moves to a neighboring month.
+    /// Use `TransitionDay::nth` to check.
+    Nth(u8, DayOfTheWeek),
+
+    /// The last given day of the week of the month.
+    Last(DayOfTheWeek),
+
+    /// The first given day of the week on or after the given day of the month,
+    /// for example `OnOrAfter(2, Sunday)` for the first Sunday after the first Saturday.
+    OnOrAfter(u8, DayOfTheWeek),
+}
+
+impl TransitionDay {
+    /// The given day of the week in the given week of the month, like in POSIX `TZ` strings:
+    /// 1 to 4 is `Nth`, 5 is `Last` (the fifth if there is one, otherwise the fourth).
+    /// Return an error for other weeks.
+    pub fn nth(week: u8, day_of_the_week: DayOfTheWeek) -> Result<Self, InvalidDateTime> {
+        match week {
+            1..=4 => Ok(TransitionDay::Nth(week, day_of_the_week)),
+            5 => Ok(TransitionDay::Last(day_of_the_week)),
+            _ => Err(InvalidDateTime::WeekOutOfRange),
+        }
+    }
+}
+
+/// Which clock the time of a transition is measured by.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum TransitionClock {
+    Utc,
+    /// Local standard time, even when DST is in effect.
+    Standard,
+    /// Local time in effect just before the transition.
+    Wall,
+}
+
+const SECONDS_PER_HOUR: i32 = 3600;
+
+const fn hours(hours: i32) -> FixedOffsetFromUtc {
+    FixedOffsetFromUtc::from_hours_and_minutes(hours, 0)
+}
+
+const fn transition(month: Month, day: TransitionDay, hours: i32, clock: TransitionClock)
+                    -> DstTransition {
+    DstTransition { month, day, time: hours * SECONDS_PER_HOUR, clock }
+}
+
+/// The European Union rules since 1996: last Sunday of March to last Sunday of October,
+/// at 1 am UTC.
+const fn europe(standard_hours: i32) -> DstRule {
+    DstRule {
+        standard_offset: hours(standard_hours),
+        dst_offset: hours(standard_hours + 1),
+        start: transition(March, TransitionDay::Last(Sunday), 1, TransitionClock::Utc),
+        end: transition(October, TransitionDay::Last(Sunday), 1, TransitionClock::Utc),
+    }
+}
+
+/// The United States and Canada rules since 2007: second Sunday of March to
+/// first Sunday of November, at 2 am local time.
+const fn north_america(standard_hours: i32) -> DstRule {
+    DstRule {
+        standard_offset: hours(standard_hours),
+        dst_offset: hours(standard_hours + 1),
+        start: transition(March, TransitionDay::Nth(2, Sunday), 2, TransitionClock::Wall),
+        end: transition(November, TransitionDay::Nth(1, Sunday), 2, TransitionClock::Wall),
+    }
+}
+
+impl DstRule {
+    /// EST / EDT, UTC-05:00 / UTC-04:00, in the United States and Canada.
+    pub const NORTH_AMERICA_EASTERN: DstRule = north_america(-5);
+
+    /// CST / CDT, UTC-06:00 / UTC-05:00, in the United States and Canada.
+    pub const NORTH_AMERICA_CENTRAL: DstRule = north_america(-6);
+
+    /// MST / MDT, UTC-07:00 / UTC-06:00, in the United States and Canada.
+    pub const NORTH_AMERICA_MOUNTAIN: DstRule = north_america(-7);
+
+    /// PST / PDT, UTC-08:00 / UTC-07:00, in the United States and Canada.
+    pub const NORTH_AMERICA_PACIFIC: DstRule = north_america(-8);
+
+    /// GMT / BST, UTC / UTC+01:00, in the United Kingdom and Ireland.
+    pub const UK_IRELAND: DstRule = europe(0);
+
+    /// WET / WEST, UTC / UTC+01:00, for example in Portugal.
+    ///
+    /// The same rule as `UK_IRELAND` under another name:
+    /// only the abbreviations differ, and `DstRule` does not have any.
+    pub const WESTERN_EUROPE: DstRule = DstRule::UK_IRELAND;
+
+    /// CET / CEST, UTC+01:00 / UTC+02:00, for example in Germany.
+    pub const CENTRAL_EUROPE: DstRule = europe(1);
+
+    /// EET / EEST, UTC+02:00 / UTC+03:00, for example in Finland.
+    pub const EASTERN_EUROPE: DstRule = europe(2);
+
+    /// AEST / AEDT, UTC+10:00 / UTC+11:00, in New South Wales, Victoria, Tasmania
+    /// and the Australian Capital Territory since 2008:
+    /// first Sunday of October to first Sunday of April, at 2 am standard time.
+    pub const AUSTRALIA_EASTERN: DstRule = DstRule {
+        standard_offset: hours(10),
+        dst_offset: hours(11),
+        start: transition(October, TransitionDay::Nth(1, Sunday), 2, TransitionClock::Standard),
+        end: transition(April, TransitionDay::Nth(1, Sunday), 2, TransitionClock::Standard),
+    };
+
+    /// NZST / NZDT, UTC+12:00 / UTC+13:00, in New Zealand since 2007:
+    /// last Sunday of September to first Sunday of April, at 2 am standard time.
+    pub const NEW_ZEALAND: DstRule = DstRule {
+        standard_offset: hours(12),
+        dst_offset: hours(13),
+        start: transition(September, TransitionDay::Last(Sunday), 2, TransitionClock::Standard),
+        end: transition(April, TransitionDay::Nth(1, Sunday), 2, TransitionClock::Standard),
+    };
+
+    /// UTC-04:00 / UTC-03:00 in mainland Chile since 2023:
+    /// from the first Sunday of September after the first Saturday, at 4 am UTC,
+    /// to the first Sunday of April after the first Saturday, at 3 am UTC.
+    pub const CHILE: DstRule = DstRule {
+        standard_offset: hours(-4),
+        dst_offset: hours(-3),
+        start: transition(September, TransitionDay::OnOrAfter(2, Sunday), 4, TransitionClock::Utc),
+        end: transition(April, TransitionDay::OnOrAfter(2, Sunday), 3, TransitionClock::Utc),
+    };
+}
+
+impl DaylightSaving for DstRule {
+    fn offset_outside_dst(&self) -> FixedOffsetFromUtc {
+        self.standard_offset
+    }
+
+    fn offset_during_dst(&self) -> FixedOffsetFromUtc {
+        self.dst_offset
+    }
+
+    fn is_in_dst(&self, t: UnixTimestamp) -> bool {
+        // DST starts in standard time and ends in daylight saving time.
+        let start_at = |year| self.start.timestamp(year, self.standard_offset, self.standard_offset);
+        let end_at = |year| self.end.timestamp(year, self.dst_offset, self.standard_offset);
+
+        // Transition times beyond 24 hours can move a transition into a neighboring year.
+        let year = Utc.from_timestamp(t).year;
+        (year.saturating_sub(1)..=year.saturating_add(1)).any(|year| {
+            let start = start_at(year);
+            let mut end = end_at(year);
+            if end <= start {
+                // Southern hemisphere: DST continues into the next year.
+                end = end_at(year.saturating_add(1))
+            }
+            start <= t.0 && t.0 < end
+        })
+    }
+
+    fn next_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        yearly_dst_change(self, t, true, |year| start_and_end(self, year))
+    }
+
+    fn previous_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        yearly_dst_change(self, t, false, |year| start_and_end(self, year))
+    }
+}
+
+/// When DST starts and ends in `year`, in seconds since 1970-01-01 00:00:00 UTC.
+pub fn start_and_end(rule: &DstRule, year: i32) -> [i64; 2] {
+    // DST starts in standard time and ends in daylight saving time.
+    [rule.start.timestamp(year, rule.standard_offset, rule.standard_offset),
+     rule.end.timestamp(year, rule.dst_offset, rule.standard_offset)]
+}
+
+impl DstTransition {
+    /// Seconds since 1970-01-01 00:00:00 UTC.
+    fn timestamp(&self, year: i32, wall_offset: FixedOffsetFromUtc,
+                 standard_offset: FixedOffsetFromUtc) -> i64 {
+        let first = NaiveDate::new(year, self.month, 1);
+        let first_day_of_the_week = i32::from(first.day_of_the_week().to_iso_number());
+        // The first `day_of_the_week` on or after `day`
+        let on_or_after = |day: i32, day_of_the_week: DayOfTheWeek| {
+            let iso_number = i32::from(day_of_the_week.to_iso_number());
+            day + positive_rem(iso_number - (first_day_of_the_week + day - 1), 7)
+        };
+        let day = match self.day {
+            TransitionDay::Nth(n, day_of_the_week) => {
+                on_or_after(7 * i32::from(n) - 6, day_of_the_week)
+            }
+            TransitionDay::OnOrAfter(day, day_of_the_week) => {
+                on_or_after(i32::from(day), day_of_the_week)
+            }
+            TransitionDay::Last(day_of_the_week) => {
+                let length = i32::from(self.month.length(YearKind::from(year)));
+                on_or_after(length - 6, day_of_the_week)
+            }
+        };
+        let offset = match self.clock {
+            TransitionClock::Utc => 0,
+            TransitionClock::Standard => standard_offset.seconds_ahead_of_utc(),
+            TransitionClock::Wall => wall_offset.seconds_ahead_of_utc(),
+        };
+        let days = first.days_since_unix() + day - 1;
+        i64::from(days) * i64::from(24 * SECONDS_PER_HOUR) + i64::from(self.time - offset)
+    }
+}
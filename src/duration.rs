@@ -0,0 +1,280 @@
+use core::ops::{Add, Sub, Neg};
+use num::{div_floor, positive_rem};
+use super::{UnixTimestamp, UnixTimestampNanos, DateTime, TimeZone, UnambiguousTimeZone,
+            LocalTimeConversionError};
+use time_zones::is_in_supported_range;
+
+const NANOSECONDS_PER_SECOND: u32 = 1_000_000_000;
+
+/// A signed amount of time, with nanosecond resolution.
+///
+/// Like in `UnixTimestampNanos`, the fraction of a second counts forward from a whole number
+/// of seconds, even for negative durations.
+/// For example, minus half a second is minus one second plus 500 million nanoseconds.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Default)]
+pub struct Duration {
+    seconds: i64,
+    nanoseconds: u32,
+}
+
+/// Returned by checked arithmetic on `DateTime` and `NaiveDateTime`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ArithmeticError {
+    /// The result is out of the range of `i64` seconds or `i32` years.
+    Overflow,
+
+    /// The resulting day does not exist in the resulting month,
+    /// with `DayOverflow::Reject`.
+    DayOutOfRange,
+
+    /// The initial date-time could not be converted to an instant in its time zone.
+    LocalTimeConversion(LocalTimeConversionError),
+}
+
+impl From<LocalTimeConversionError> for ArithmeticError {
+    fn from(error: LocalTimeConversionError) -> Self {
+        ArithmeticError::LocalTimeConversion(error)
+    }
+}
+
+impl Duration {
+    /// Panics if `nanoseconds` is one billion or more.
+    pub fn new(seconds: i64, nanoseconds: u32) -> Self {
+        assert!(nanoseconds < NANOSECONDS_PER_SECOND, "nanoseconds out of range");
+        Duration { seconds, nanoseconds }
+    }
+
+    pub fn from_seconds(seconds: i64) -> Self {
+        Duration { seconds, nanoseconds: 0 }
+    }
+
+    /// Panics on overflow.
+    pub fn from_minutes(minutes: i64) -> Self {
+        Duration::from_seconds(minutes.checked_mul(60).expect("overflow in Duration::from_minutes"))
+    }
+
+    /// Panics on overflow.
+    pub fn from_hours(hours: i64) -> Self {
+        Duration::from_seconds(hours.checked_mul(3600).expect("overflow in Duration::from_hours"))
+    }
+
+    /// 24 hours. Panics on overflow.
+    pub fn from_days(days: i64) -> Self {
+        Duration::from_seconds(days.checked_mul(86400).expect("overflow in Duration::from_days"))
+    }
+
+    pub fn from_milliseconds(milliseconds: i64) -> Self {
+        Duration::from_nanoseconds_with_unit(milliseconds, 1_000)
+    }
+
+    pub fn from_microseconds(microseconds: i64) -> Self {
+        Duration::from_nanoseconds_with_unit(microseconds, 1_000_000)
+    }
+
+    pub fn from_nanoseconds(nanoseconds: i64) -> Self {
+        Duration::from_nanoseconds_with_unit(nanoseconds, NANOSECONDS_PER_SECOND)
+    }
+
+    fn from_nanoseconds_with_unit(amount: i64, units_per_second: u32) -> Self {
+        let units_per_second = i64::from(units_per_second);
+        let nanoseconds_per_unit = i64::from(NANOSECONDS_PER_SECOND) / units_per_second;
+        Duration {
+            seconds: div_floor(amount, units_per_second),
+            nanoseconds: (positive_rem(amount, units_per_second) * nanoseconds_per_unit) as u32,
+        }
+    }
+
+    /// Whole seconds, rounded towards negative infinity.
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// The fraction of a second to add to `seconds()`, between 0 and 999_999_999.
+    pub fn nanoseconds(&self) -> u32 {
+        self.nanoseconds
+    }
+
+    pub fn checked_add(self, other: Duration) -> Option<Duration> {
+        add(self.seconds, self.nanoseconds, other)
+            .map(|(seconds, nanoseconds)| Duration { seconds, nanoseconds })
+    }
+
+    pub fn checked_sub(self, other: Duration) -> Option<Duration> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Duration> {
+        if self.nanoseconds == 0 {
+            Some(Duration { seconds: self.seconds.checked_neg()?, nanoseconds: 0 })
+        } else {
+            Some(Duration {
+                seconds: self.seconds.checked_add(1)?.checked_neg()?,
+                nanoseconds: NANOSECONDS_PER_SECOND - self.nanoseconds,
+            })
+        }
+    }
+}
+
+/// Add a duration to a number of seconds and nanoseconds, with the same normalization.
+fn add(seconds: i64, nanoseconds: u32, duration: Duration) -> Option<(i64, u32)> {
+    let mut seconds = seconds.checked_add(duration.seconds)?;
+    let mut nanoseconds = nanoseconds + duration.nanoseconds;
+    if nanoseconds >= NANOSECONDS_PER_SECOND {
+        nanoseconds -= NANOSECONDS_PER_SECOND;
+        seconds = seconds.checked_add(1)?;
+    }
+    Some((seconds, nanoseconds))
+}
+
+/// Like `checked_sub`, but for two `(seconds, nanoseconds)` pairs.
+fn difference(seconds_a: i64, nanoseconds_a: u32, seconds_b: i64, nanoseconds_b: u32)
+              -> Option<Duration> {
+    let seconds = seconds_a.checked_sub(seconds_b)?;
+    if nanoseconds_a >= nanoseconds_b {
+        Some(Duration { seconds, nanoseconds: nanoseconds_a - nanoseconds_b })
+    } else {
+        Some(Duration {
+            seconds: seconds.checked_sub(1)?,
+            nanoseconds: NANOSECONDS_PER_SECOND + nanoseconds_a - nanoseconds_b,
+        })
+    }
+}
+
+impl UnixTimestamp {
+    /// The sub-second part of the result, if any, is rounded towards the past.
+    pub fn checked_add(self, duration: Duration) -> Option<UnixTimestamp> {
+        add(self.0, 0, duration).map(|(seconds, _)| UnixTimestamp(seconds))
+    }
+
+    /// The sub-second part of the result, if any, is rounded towards the past.
+    pub fn checked_sub(self, duration: Duration) -> Option<UnixTimestamp> {
+        self.checked_add(duration.checked_neg()?)
+    }
+
+    /// How much time passed from `earlier` to `self`. Negative if `earlier` is in fact later.
+    pub fn checked_duration_since(self, earlier: UnixTimestamp) -> Option<Duration> {
+        difference(self.0, 0, earlier.0, 0)
+    }
+}
+
+impl UnixTimestampNanos {
+    pub fn checked_add(self, duration: Duration) -> Option<UnixTimestampNanos> {
+        add(self.seconds, self.nanoseconds, duration)
+            .map(|(seconds, nanoseconds)| UnixTimestampNanos { seconds, nanoseconds })
+    }
+
+    pub fn checked_sub(self, duration: Duration) -> Option<UnixTimestampNanos> {
+        self.checked_add(duration.checked_neg()?)
+    }
+
+    /// How much time passed from `earlier` to `self`. Negative if `earlier` is in fact later.
+    pub fn checked_duration_since(self, earlier: UnixTimestampNanos) -> Option<Duration> {
+        difference(self.seconds, self.nanoseconds, earlier.seconds, earlier.nanoseconds)
+    }
+}
+
+impl<Tz: TimeZone + Clone> DateTime<Tz> {
+    /// Add an exact amount of time, then convert the resulting instant back to this time zone.
+    ///
+    /// Across a change of UTC offset (such as the start or end of daylight saving time)
+    /// the local time changes by more or less than `duration`.
+    ///
+    /// Return `ArithmeticError::Overflow` if the result is beyond the range of `i32` years.
+    pub fn checked_add(&self, duration: Duration) -> Result<Self, ArithmeticError> {
+        let t = self.to_timestamp_nanos()?;
+        let t = t.checked_add(duration).ok_or(ArithmeticError::Overflow)?;
+        if !is_in_supported_range(UnixTimestamp(t.seconds)) {
+            return Err(ArithmeticError::Overflow)
+        }
+        Ok(DateTime::from_timestamp_nanos(t, self.time_zone.clone()))
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Result<Self, ArithmeticError> {
+        self.checked_add(duration.checked_neg().ok_or(ArithmeticError::Overflow)?)
+    }
+
+    /// How much time passed between the two instants.
+    /// Negative if `earlier` is in fact later.
+    pub fn checked_duration_since<OtherTz: TimeZone>(&self, earlier: &DateTime<OtherTz>)
+                                                     -> Result<Duration, ArithmeticError> {
+        self.to_timestamp_nanos()?
+            .checked_duration_since(earlier.to_timestamp_nanos()?)
+            .ok_or(ArithmeticError::Overflow)
+    }
+}
+
+macro_rules! operators {
+    ($( $Type: ty, $Output: ty, $Other: ty, $message: expr; )+) => {
+        $(
+            impl Add<$Other> for $Type {
+                type Output = $Output;
+
+                /// Panics on overflow.
+                fn add(self, other: $Other) -> $Output {
+                    self.checked_add(other).expect($message)
+                }
+            }
+
+            impl Sub<$Other> for $Type {
+                type Output = $Output;
+
+                /// Panics on overflow.
+                fn sub(self, other: $Other) -> $Output {
+                    self.checked_sub(other).expect($message)
+                }
+            }
+        )+
+    }
+}
+
+operators! {
+    Duration, Duration, Duration, "overflow in Duration arithmetic";
+    UnixTimestamp, UnixTimestamp, Duration, "overflow in UnixTimestamp arithmetic";
+    UnixTimestampNanos, UnixTimestampNanos, Duration,
+        "overflow in UnixTimestampNanos arithmetic";
+}
+
+impl Sub<UnixTimestamp> for UnixTimestamp {
+    type Output = Duration;
+
+    /// Panics on overflow.
+    fn sub(self, other: UnixTimestamp) -> Duration {
+        self.checked_duration_since(other).expect("overflow in UnixTimestamp arithmetic")
+    }
+}
+
+impl Sub<UnixTimestampNanos> for UnixTimestampNanos {
+    type Output = Duration;
+
+    /// Panics on overflow.
+    fn sub(self, other: UnixTimestampNanos) -> Duration {
+        self.checked_duration_since(other).expect("overflow in UnixTimestampNanos arithmetic")
+    }
+}
+
+impl Neg for Duration {
+    type Output = Duration;
+
+    /// Panics on overflow.
+    fn neg(self) -> Duration {
+        self.checked_neg().expect("overflow in Duration arithmetic")
+    }
+}
+
+impl<Tz: UnambiguousTimeZone + Clone> Add<Duration> for DateTime<Tz> {
+    type Output = DateTime<Tz>;
+
+    /// Panics on overflow.
+    fn add(self, duration: Duration) -> DateTime<Tz> {
+        self.checked_add(duration).expect("overflow in DateTime arithmetic")
+    }
+}
+
+impl<Tz: UnambiguousTimeZone + Clone> Sub<Duration> for DateTime<Tz> {
+    type Output = DateTime<Tz>;
+
+    /// Panics on overflow.
+    fn sub(self, duration: Duration) -> DateTime<Tz> {
+        self.checked_sub(duration).expect("overflow in DateTime arithmetic")
+    }
+}
@@ -0,0 +1,111 @@
+//! ISO 8601 week dates.
+
+use core::fmt;
+use super::{NaiveDateTime, NaiveDate, DateTime, TimeZone, Month, DayOfTheWeek, InvalidDateTime};
+
+/// A date in the ISO 8601 week-numbering calendar, such as `2009-W53-7` for 2010-01-03.
+///
+/// Weeks start on Monday. Week 1 of a week-numbering year is the week with its first Thursday,
+/// so the week-numbering year can differ from the Gregorian year for a few days
+/// around January 1st.
+///
+/// ```rust
+/// use gregor::{NaiveDate, IsoWeekDate, DayOfTheWeek};
+/// use gregor::Month::*;
+/// let d = NaiveDate::new(2010, January, 3);
+/// assert_eq!(d.iso_week_date(), IsoWeekDate::new(2009, 53, DayOfTheWeek::Sunday));
+/// assert_eq!(NaiveDate::from(IsoWeekDate::new(2009, 53, DayOfTheWeek::Sunday)), d);
+/// ```
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct IsoWeekDate {
+    /// Week-numbering year, per ISO 8601. See `NaiveDateTime::year`.
+    pub year: i32,
+
+    /// The first week of the year is week 1. The last is week 52 or 53.
+    pub week: u8,
+
+    pub weekday: DayOfTheWeek,
+}
+
+impl fmt::Debug for IsoWeekDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:04}-W{:02}-{}", self.year, self.week, self.weekday.to_iso_number())
+    }
+}
+
+impl IsoWeekDate {
+    pub fn new(year: i32, week: u8, weekday: DayOfTheWeek) -> Self {
+        IsoWeekDate { year, week, weekday }
+    }
+
+    /// Like `new`, but return an error if the week does not exist in this week-numbering year.
+    pub fn try_new(year: i32, week: u8, weekday: DayOfTheWeek) -> Result<Self, InvalidDateTime> {
+        let d = IsoWeekDate::new(year, week, weekday);
+        d.check_fields()?;
+        Ok(d)
+    }
+
+    /// Whether the week exists in this week-numbering year.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        if self.week < 1 || self.week > IsoWeekDate::weeks_in_year(self.year) {
+            Err(InvalidDateTime::WeekOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 52 or 53. Years with 53 weeks are those that start or end on a Thursday.
+    pub fn weeks_in_year(year: i32) -> u8 {
+        // December 28th is always in the last week of its year.
+        NaiveDate::new(year, Month::December, 28).iso_week_date().week
+    }
+}
+
+/// Day number since 1970-01-01 of the Monday of week 1.
+fn first_monday(year: i32) -> i32 {
+    // January 4th is always in week 1.
+    let january_4th = NaiveDate::new(year, Month::January, 4);
+    january_4th.days_since_unix() - i32::from(january_4th.day_of_the_week().to_iso_number()) + 1
+}
+
+/// Weeks past the last week of the year continue into the next year,
+/// and week 0 is the last week of the previous year.
+impl From<IsoWeekDate> for NaiveDate {
+    fn from(d: IsoWeekDate) -> Self {
+        let days = first_monday(d.year) + (i32::from(d.week) - 1) * 7
+                 + i32::from(d.weekday.to_iso_number()) - 1;
+        NaiveDate::from_days_since_unix(days)
+    }
+}
+
+impl NaiveDate {
+    /// The ISO 8601 week-numbering year, week number, and day of the week.
+    pub fn iso_week_date(&self) -> IsoWeekDate {
+        let weekday = self.day_of_the_week();
+        // The Thursday of the same week determines the week-numbering year.
+        let thursday = self.days_since_unix() - i32::from(weekday.to_iso_number()) + 4;
+        let year = NaiveDate::from_days_since_unix(thursday).year;
+        let week = (thursday - first_monday(year)) / 7 + 1;
+        IsoWeekDate { year, week: week as u8, weekday }
+    }
+}
+
+impl NaiveDateTime {
+    /// The ISO 8601 week-numbering year, week number, and day of the week.
+    pub fn iso_week_date(&self) -> IsoWeekDate {
+        self.date().iso_week_date()
+    }
+}
+
+impl<Tz: TimeZone> DateTime<Tz> {
+    /// The ISO 8601 week-numbering year, week number, and day of the week.
+    pub fn iso_week_date(&self) -> IsoWeekDate {
+        self.naive.iso_week_date()
+    }
+}
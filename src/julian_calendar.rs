@@ -0,0 +1,317 @@
+//! The proleptic Julian calendar, and historical calendars that switched to the Gregorian one.
+
+use core::convert::TryFrom;
+use core::fmt;
+use super::{NaiveDateTime, NaiveDate, Month, YearKind, DayOfTheWeek, InvalidDateTime, Calendar};
+use num::{div_floor, positive_rem};
+
+/// A date in the proleptic Julian calendar, where every fourth year is a leap year.
+///
+/// ```rust
+/// use gregor::{JulianDate, NaiveDate};
+/// use gregor::Month::*;
+/// // Isaac Newton’s birth
+/// let julian = JulianDate::new(1642, December, 25);
+/// assert_eq!(NaiveDate::from(julian), NaiveDate::new(1643, January, 4));
+/// assert_eq!(JulianDate::from(NaiveDate::new(1643, January, 4)), julian);
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct JulianDate {
+    /// Year number per ISO 8601. See `NaiveDateTime::year`.
+    pub year: i32,
+
+    pub month: Month,
+
+    /// 1st of the month is day 1
+    pub day: u8,
+}
+
+impl fmt::Debug for JulianDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:04}-{:02}-{:02} (Julian)", self.year, self.month.to_number(), self.day)
+    }
+}
+
+/// Days from the Julian 0000-01-01 to 1970-01-01 (Julian 1969-12-19).
+const DAYS_FROM_D0_TO_UNIX: i64 = 719_530;
+
+const DAYS_PER_4_YEARS: i64 = 4 * 365 + 1;
+
+fn year_kind(year: i32) -> YearKind {
+    if positive_rem(year, 4) == 0 {
+        YearKind::Leap
+    } else {
+        YearKind::Common
+    }
+}
+
+impl JulianDate {
+    pub fn new(year: i32, month: Month, day: u8) -> Self {
+        JulianDate { year, month, day }
+    }
+
+    /// Like `new`, but return an error if the day does not exist in this month and year.
+    pub fn try_new(year: i32, month: Month, day: u8) -> Result<Self, InvalidDateTime> {
+        let d = JulianDate::new(year, month, day);
+        d.check_fields()?;
+        Ok(d)
+    }
+
+    /// Whether the day exists in this month and year of the Julian calendar.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        if self.day < 1 || self.day > self.month.length(year_kind(self.year)) {
+            Err(InvalidDateTime::DayOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn day_of_the_week(&self) -> DayOfTheWeek {
+        NaiveDate::from(*self).day_of_the_week()
+    }
+
+    /// Day number where 1970-01-01 (Gregorian), 1969-12-19 in the Julian calendar, is day 0.
+    ///
+    /// Panics for years beyond about ±5.8 million, where this number does not fit in `i32`.
+    pub fn days_since_unix(&self) -> i32 {
+        // In 64 bits so that `year * 365` does not overflow.
+        let year = i64::from(self.year);
+        // Leap years between year 0 and this one, not including this one.
+        let leap_years = div_floor(year + 3, 4);
+        let days_since_d0 = year * 365 + leap_years
+                          + i64::from(self.month.days_since_january_1st(year_kind(self.year)))
+                          + i64::from(self.day) - 1;
+        i32::try_from(days_since_d0 - DAYS_FROM_D0_TO_UNIX)
+            .expect("date out of range for a day number")
+    }
+
+    /// The inverse of `days_since_unix`. Any `i32` is a valid day number.
+    pub fn from_days_since_unix(days: i32) -> Self {
+        // In 64 bits so that adding the epoch does not overflow. The year fits in `i32`.
+        let days_since_d0 = i64::from(days) + DAYS_FROM_D0_TO_UNIX;
+        // Cycles of four years start with a leap year.
+        let cycles = div_floor(days_since_d0, DAYS_PER_4_YEARS);
+        let mut day_of_the_year = positive_rem(days_since_d0, DAYS_PER_4_YEARS) as i32;
+        let mut year = (cycles * 4) as i32;
+        if day_of_the_year >= 366 {
+            day_of_the_year -= 366;
+            year += 1 + day_of_the_year / 365;
+            day_of_the_year %= 365;
+        }
+        let (month, day) = Month::from_day_of_the_year(day_of_the_year, year_kind(year));
+        JulianDate { year, month, day }
+    }
+}
+
+impl From<NaiveDate> for JulianDate {
+    fn from(date: NaiveDate) -> Self {
+        JulianDate::from_days_since_unix(date.days_since_unix())
+    }
+}
+
+impl From<JulianDate> for NaiveDate {
+    fn from(date: JulianDate) -> Self {
+        NaiveDate::from_days_since_unix(date.days_since_unix())
+    }
+}
+
+/// The date of this date and time. The time of day is dropped.
+impl From<NaiveDateTime> for JulianDate {
+    fn from(d: NaiveDateTime) -> Self {
+        JulianDate::from(d.date())
+    }
+}
+
+/// Midnight at the start of this day.
+impl From<JulianDate> for NaiveDateTime {
+    fn from(date: JulianDate) -> Self {
+        NaiveDate::from(date).into()
+    }
+}
+
+/// The proleptic Julian calendar, as used by `JulianDate`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+pub struct Julian;
+
+impl Calendar for Julian {
+    type Date = JulianDate;
+    type Month = Month;
+    type YearKind = YearKind;
+
+    fn year_kind(&self, year: i32) -> YearKind {
+        year_kind(year)
+    }
+
+    fn month_length(&self, year: i32, month: Month) -> u8 {
+        month.length(year_kind(year))
+    }
+
+    fn date(&self, year: i32, month: Month, day: u8) -> Result<JulianDate, InvalidDateTime> {
+        JulianDate::try_new(year, month, day)
+    }
+
+    fn days_since_unix(&self, date: &JulianDate) -> i32 {
+        date.days_since_unix()
+    }
+
+    fn date_from_days_since_unix(&self, days: i32) -> JulianDate {
+        JulianDate::from_days_since_unix(days)
+    }
+}
+
+/// A calendar that is Julian before a cutover and Gregorian from it,
+/// as adopted at different times in different countries.
+///
+/// Around the cutover, some days in the Julian calendar were skipped:
+/// in the default calendar, Thursday 1582-10-04 was followed by Friday 1582-10-15.
+///
+/// ```rust
+/// use gregor::{HistoricalCalendar, HistoricalDate, NaiveDate};
+/// use gregor::Month::*;
+/// let calendar = HistoricalCalendar::GREAT_BRITAIN;
+/// assert_eq!(calendar.month_length(1752, September), 19);
+/// assert!(calendar.date(1752, September, 10).is_err());
+/// let d = calendar.date(1752, September, 2).unwrap();
+/// assert_eq!(NaiveDate::from(d), NaiveDate::new(1752, September, 13));
+/// ```
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct HistoricalCalendar {
+    /// The first day of the Gregorian calendar.
+    pub first_gregorian_day: NaiveDate,
+}
+
+/// A date in a `HistoricalCalendar`.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum HistoricalDate {
+    Julian(JulianDate),
+    Gregorian(NaiveDate),
+}
+
+impl HistoricalCalendar {
+    /// Gregorian from 1582-10-15, as introduced by the papal bull *Inter gravissimas*
+    /// in Spain, Portugal, Poland and most of Italy.
+    pub const GREGORIAN_REFORM: HistoricalCalendar = HistoricalCalendar {
+        first_gregorian_day: NaiveDate { year: 1582, month: Month::October, day: 15 },
+    };
+
+    /// Gregorian from 1752-09-14 in Great Britain and its colonies.
+    pub const GREAT_BRITAIN: HistoricalCalendar = HistoricalCalendar {
+        first_gregorian_day: NaiveDate { year: 1752, month: Month::September, day: 14 },
+    };
+
+    pub fn new(first_gregorian_day: NaiveDate) -> Self {
+        HistoricalCalendar { first_gregorian_day }
+    }
+
+    fn last_julian_day(&self) -> JulianDate {
+        JulianDate::from_days_since_unix(self.first_gregorian_day.days_since_unix() - 1)
+    }
+
+    /// The date in this calendar of a day given in the Gregorian calendar.
+    pub fn from_gregorian(&self, date: NaiveDate) -> HistoricalDate {
+        if date >= self.first_gregorian_day {
+            HistoricalDate::Gregorian(date)
+        } else {
+            HistoricalDate::Julian(JulianDate::from(date))
+        }
+    }
+
+    /// The date with these fields in this calendar.
+    ///
+    /// Return an error if the day does not exist in this month and year,
+    /// including days skipped at the cutover.
+    pub fn date(&self, year: i32, month: Month, day: u8)
+                -> Result<HistoricalDate, InvalidDateTime> {
+        if NaiveDate::new(year, month, day) >= self.first_gregorian_day {
+            NaiveDate::try_new(year, month, day).map(HistoricalDate::Gregorian)
+        } else {
+            let date = JulianDate::try_new(year, month, day)?;
+            if date <= self.last_julian_day() {
+                Ok(HistoricalDate::Julian(date))
+            } else {
+                Err(InvalidDateTime::DayOutOfRange)
+            }
+        }
+    }
+
+    /// The number of days in this month and year, not counting days skipped at the cutover.
+    pub fn month_length(&self, year: i32, month: Month) -> u8 {
+        (1..=31).filter(|&day| self.date(year, month, day).is_ok()).count() as u8
+    }
+}
+
+impl Calendar for HistoricalCalendar {
+    type Date = HistoricalDate;
+    type Month = Month;
+    type YearKind = YearKind;
+
+    /// Leap if February has 29 days in this year.
+    fn year_kind(&self, year: i32) -> YearKind {
+        if HistoricalCalendar::month_length(self, year, Month::February) == 29 {
+            YearKind::Leap
+        } else {
+            YearKind::Common
+        }
+    }
+
+    fn month_length(&self, year: i32, month: Month) -> u8 {
+        HistoricalCalendar::month_length(self, year, month)
+    }
+
+    fn date(&self, year: i32, month: Month, day: u8) -> Result<HistoricalDate, InvalidDateTime> {
+        HistoricalCalendar::date(self, year, month, day)
+    }
+
+    fn days_since_unix(&self, date: &HistoricalDate) -> i32 {
+        NaiveDate::from(*date).days_since_unix()
+    }
+
+    fn date_from_days_since_unix(&self, days: i32) -> HistoricalDate {
+        self.from_gregorian(NaiveDate::from_days_since_unix(days))
+    }
+}
+
+/// Gregorian from 1582-10-15
+impl Default for HistoricalCalendar {
+    fn default() -> Self {
+        HistoricalCalendar::GREGORIAN_REFORM
+    }
+}
+
+impl HistoricalDate {
+    pub fn year(&self) -> i32 {
+        match *self {
+            HistoricalDate::Julian(d) => d.year,
+            HistoricalDate::Gregorian(d) => d.year,
+        }
+    }
+
+    pub fn month(&self) -> Month {
+        match *self {
+            HistoricalDate::Julian(d) => d.month,
+            HistoricalDate::Gregorian(d) => d.month,
+        }
+    }
+
+    pub fn day(&self) -> u8 {
+        match *self {
+            HistoricalDate::Julian(d) => d.day,
+            HistoricalDate::Gregorian(d) => d.day,
+        }
+    }
+}
+
+impl From<HistoricalDate> for NaiveDate {
+    fn from(date: HistoricalDate) -> Self {
+        match date {
+            HistoricalDate::Julian(d) => d.into(),
+            HistoricalDate::Gregorian(d) => d,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Day numbers used in astronomy and other fields: the Julian Day Number, Julian Date,
+//! Modified Julian Date, and Rata Die.
+
+use core::convert::TryFrom;
+use super::{NaiveDate, NaiveDateTime, UnixTimestamp, UnixTimestampNanos, TimeZone,
+            UnambiguousTimeZone, Utc};
+use num::{div_floor, positive_rem};
+
+/// The Julian Day Number of 1970-01-01. Day 0 is -4713-11-24 in the proleptic Gregorian calendar.
+const JULIAN_DAY_NUMBER_OF_UNIX_EPOCH: i64 = 2_440_588;
+
+/// The Modified Julian Day of 1970-01-01. Day 0 is 1858-11-17.
+const MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH: i64 = 40_587;
+
+/// The Rata Die of 1970-01-01. Day 1 is 0001-01-01 in the proleptic Gregorian calendar.
+const RATA_DIE_OF_UNIX_EPOCH: i64 = 719_163;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const NANOSECONDS_PER_DAY: f64 = 86_400e9;
+
+/// The difference between Julian Dates and Modified Julian Dates.
+const MODIFIED_JULIAN_DATE_OFFSET: f64 = 2_400_000.5;
+
+/// The date `days` after the day numbered `epoch`,
+/// or `None` if its day number since 1970-01-01 does not fit in `i32`.
+fn date_from_day_number(days: i64, epoch: i64) -> Option<NaiveDate> {
+    let days_since_unix = i32::try_from(days.checked_sub(epoch)?).ok()?;
+    Some(NaiveDate::from_days_since_unix(days_since_unix))
+}
+
+/// Day numbers are `i64` since adding an epoch to `days_since_unix` may not fit in `i32`.
+/// Their inverses return `None` for days out of the range of `from_days_since_unix`.
+impl NaiveDate {
+    /// The Julian Day Number, a count of days where -4713-11-24 is day 0.
+    ///
+    /// This is the Julian Date at noon UT on this day.
+    ///
+    /// ```rust
+    /// use gregor::NaiveDate;
+    /// use gregor::Month::*;
+    /// assert_eq!(NaiveDate::new(2000, January, 1).julian_day_number(), 2_451_545);
+    /// ```
+    pub fn julian_day_number(&self) -> i64 {
+        i64::from(self.days_since_unix()) + JULIAN_DAY_NUMBER_OF_UNIX_EPOCH
+    }
+
+    /// The inverse of `julian_day_number`.
+    pub fn from_julian_day_number(julian_day_number: i64) -> Option<Self> {
+        date_from_day_number(julian_day_number, JULIAN_DAY_NUMBER_OF_UNIX_EPOCH)
+    }
+
+    /// The Modified Julian Day, a count of days where 1858-11-17 is day 0.
+    ///
+    /// This is the Modified Julian Date at midnight UT at the start of this day.
+    pub fn modified_julian_day(&self) -> i64 {
+        i64::from(self.days_since_unix()) + MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH
+    }
+
+    /// The inverse of `modified_julian_day`.
+    pub fn from_modified_julian_day(modified_julian_day: i64) -> Option<Self> {
+        date_from_day_number(modified_julian_day, MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH)
+    }
+
+    /// The Rata Die, a count of days where 0001-01-01 is day 1.
+    pub fn rata_die(&self) -> i64 {
+        i64::from(self.days_since_unix()) + RATA_DIE_OF_UNIX_EPOCH
+    }
+
+    /// The inverse of `rata_die`.
+    pub fn from_rata_die(rata_die: i64) -> Option<Self> {
+        date_from_day_number(rata_die, RATA_DIE_OF_UNIX_EPOCH)
+    }
+}
+
+/// The date and time are taken as UT.
+impl NaiveDateTime {
+    /// The Julian Date: days and fractions of a day since noon UT on -4713-11-24.
+    ///
+    /// ```rust
+    /// use gregor::NaiveDateTime;
+    /// use gregor::Month::*;
+    /// let j2000 = NaiveDateTime::new(2000, January, 1, 12, 0, 0);
+    /// assert_eq!(j2000.julian_date(), 2_451_545.0);
+    /// assert_eq!(NaiveDateTime::from_julian_date(2_451_545.0), Some(j2000));
+    /// ```
+    pub fn julian_date(&self) -> f64 {
+        Utc.to_unambiguous_timestamp_nanos(self).julian_date()
+    }
+
+    /// The Modified Julian Date: days and fractions of a day since midnight UT on 1858-11-17.
+    pub fn modified_julian_date(&self) -> f64 {
+        Utc.to_unambiguous_timestamp_nanos(self).modified_julian_date()
+    }
+
+    /// The inverse of `julian_date`, rounded to the nearest nanosecond.
+    /// See `UnixTimestampNanos::from_julian_date`.
+    pub fn from_julian_date(julian_date: f64) -> Option<Self> {
+        UnixTimestampNanos::from_julian_date(julian_date).map(|t| Utc.from_timestamp_nanos(t))
+    }
+
+    /// The inverse of `modified_julian_date`, rounded to the nearest nanosecond.
+    /// See `UnixTimestampNanos::from_modified_julian_date`.
+    pub fn from_modified_julian_date(modified_julian_date: f64) -> Option<Self> {
+        UnixTimestampNanos::from_modified_julian_date(modified_julian_date)
+            .map(|t| Utc.from_timestamp_nanos(t))
+    }
+}
+
+impl UnixTimestamp {
+    /// The Julian Date: days and fractions of a day since noon UT on -4713-11-24.
+    pub fn julian_date(&self) -> f64 {
+        UnixTimestampNanos::from(*self).julian_date()
+    }
+
+    /// The Modified Julian Date: days and fractions of a day since midnight UT on 1858-11-17.
+    pub fn modified_julian_date(&self) -> f64 {
+        UnixTimestampNanos::from(*self).modified_julian_date()
+    }
+
+    /// The inverse of `julian_date`, rounded to the nearest second.
+    /// See `UnixTimestampNanos::from_julian_date`.
+    pub fn from_julian_date(julian_date: f64) -> Option<Self> {
+        UnixTimestamp::from_modified_julian_date(julian_date - MODIFIED_JULIAN_DATE_OFFSET)
+    }
+
+    /// The inverse of `modified_julian_date`, rounded to the nearest second.
+    /// See `UnixTimestampNanos::from_modified_julian_date`.
+    pub fn from_modified_julian_date(modified_julian_date: f64) -> Option<Self> {
+        let t = UnixTimestampNanos::from_modified_julian_date(modified_julian_date)?;
+        Some(UnixTimestamp(t.seconds + i64::from(t.nanoseconds >= 500_000_000)))
+    }
+}
+
+impl UnixTimestampNanos {
+    /// The Julian Date: days and fractions of a day since noon UT on -4713-11-24.
+    ///
+    /// A `f64` Julian Date for recent years is precise to about 40 microseconds.
+    pub fn julian_date(&self) -> f64 {
+        self.modified_julian_date() + MODIFIED_JULIAN_DATE_OFFSET
+    }
+
+    /// The Modified Julian Date: days and fractions of a day since midnight UT on 1858-11-17.
+    ///
+    /// ```rust
+    /// use gregor::UnixTimestampNanos;
+    /// let t = UnixTimestampNanos { seconds: 43_200, nanoseconds: 0 };
+    /// assert_eq!(t.modified_julian_date(), 40_587.5);
+    /// ```
+    pub fn modified_julian_date(&self) -> f64 {
+        // Add the whole days and the fraction separately, to keep precision.
+        let days = div_floor(self.seconds, SECONDS_PER_DAY);
+        let seconds = positive_rem(self.seconds, SECONDS_PER_DAY);
+        let nanoseconds = seconds as f64 * 1e9 + f64::from(self.nanoseconds);
+        (days + MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH) as f64
+            + nanoseconds / NANOSECONDS_PER_DAY
+    }
+
+    /// The inverse of `julian_date`, rounded to the nearest nanosecond.
+    ///
+    /// Return `None` for NaN, infinities, and dates whose day number since 1970-01-01
+    /// does not fit in `i32`.
+    pub fn from_julian_date(julian_date: f64) -> Option<Self> {
+        UnixTimestampNanos::from_modified_julian_date(julian_date - MODIFIED_JULIAN_DATE_OFFSET)
+    }
+
+    /// The inverse of `modified_julian_date`, rounded to the nearest nanosecond.
+    ///
+    /// Return `None` for NaN, infinities, and dates whose day number since 1970-01-01
+    /// does not fit in `i32`.
+    pub fn from_modified_julian_date(modified_julian_date: f64) -> Option<Self> {
+        let days_since_unix = modified_julian_date - MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH as f64;
+        // Also false for NaN
+        if !(f64::from(i32::MIN) <= days_since_unix && days_since_unix < f64::from(i32::MAX)) {
+            return None
+        }
+        // Round down to whole days. (`f64::floor` is not available without `std`.)
+        let mut days = modified_julian_date as i64;
+        if days as f64 > modified_julian_date {
+            days -= 1
+        }
+        let fraction = modified_julian_date - days as f64;
+        let nanoseconds = (fraction * NANOSECONDS_PER_DAY + 0.5) as i64;
+        let days = days - MODIFIED_JULIAN_DAY_OF_UNIX_EPOCH;
+        Some(UnixTimestampNanos {
+            seconds: days * SECONDS_PER_DAY + nanoseconds / 1_000_000_000,
+            nanoseconds: (nanoseconds % 1_000_000_000) as u32,
+        })
+    }
+}
@@ -2,21 +2,71 @@
 
 #[cfg(any(test, feature = "system_time"))] #[macro_use] extern crate std;
 
+mod calendar;
+mod calendar_arithmetic;
+mod dst_rule;
+mod duration;
+mod iso_week;
+mod julian_calendar;
+mod julian_day;
 mod num;
+mod ordinal_date;
+mod posix_tz;
+mod rfc3339;
+mod strftime;
+mod strptime;
 #[cfg(feature = "system_time")] mod system_time;
 #[cfg(test)] mod tests;
 mod time_zones;
+#[cfg(feature = "tzdb")] pub mod tzdb;
+mod tzif;
+#[cfg(feature = "system_time")] mod zoneinfo;
 
 use core::fmt;
 use num::positive_rem;
-use time_zones::days_since_unix;
-pub use time_zones::{TimeZone, LocalTimeConversionError, UnambiguousTimeZone, DaylightSaving,
-                     Utc, FixedOffsetFromUtc, CentralEurope};
+use time_zones::{days_since_unix, date_from_days_since_unix};
+pub use calendar::{Calendar, Gregorian};
+pub use calendar_arithmetic::{DayOverflow, CalendarDifference};
+pub use dst_rule::{DstRule, DstTransition, TransitionDay, TransitionClock};
+pub use duration::{Duration, ArithmeticError};
+pub use iso_week::IsoWeekDate;
+pub use julian_calendar::{JulianDate, Julian, HistoricalCalendar, HistoricalDate};
+pub use ordinal_date::OrdinalDate;
+pub use posix_tz::PosixTz;
+pub use rfc3339::{ParseError, ParseErrorKind};
+pub use strftime::Formatted;
+pub use strptime::FormatParseError;
+pub use time_zones::{TimeZone, LocalTimeConversionError, Disambiguation, UnambiguousTimeZone,
+                     DaylightSaving, Utc, FixedOffsetFromUtc, CentralEurope, Transition,
+                     Transitions};
+pub use tzif::{Tzif, TzifError};
+#[cfg(feature = "system_time")] pub use zoneinfo::ZoneinfoError;
 
 /// In seconds since 1970-01-01 00:00:00 UTC.
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct UnixTimestamp(pub i64);
 
+/// Like `UnixTimestamp`, with nanosecond resolution.
+///
+/// `nanoseconds` is always in the range `0..1_000_000_000` and counts forward from `seconds`,
+/// even for instants before 1970.
+/// For example, half a second before the Unix Epoch is
+/// `UnixTimestampNanos { seconds: -1, nanoseconds: 500_000_000 }`.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct UnixTimestampNanos {
+    pub seconds: i64,
+    pub nanoseconds: u32,
+}
+
+impl From<UnixTimestamp> for UnixTimestampNanos {
+    fn from(t: UnixTimestamp) -> Self {
+        UnixTimestampNanos {
+            seconds: t.0,
+            nanoseconds: 0,
+        }
+    }
+}
+
 #[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub struct DateTime<Tz: TimeZone> {
     pub naive: NaiveDateTime,
@@ -39,6 +89,50 @@ pub struct NaiveDateTime {
     pub hour: u8,
     pub minute: u8,
     pub second: u8,
+
+    /// Fraction of the second, between 0 and 999_999_999.
+    pub nanosecond: u32,
+}
+
+/// A date in the Gregorian calendar, without a time of day.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct NaiveDate {
+    /// Year number per ISO 8601. See `NaiveDateTime::year`.
+    pub year: i32,
+
+    pub month: Month,
+
+    /// 1st of the month is day 1
+    pub day: u8,
+}
+
+/// A time of the day, without a date.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct NaiveTime {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+
+    /// Fraction of the second, between 0 and 999_999_999.
+    pub nanosecond: u32,
+}
+
+/// Returned by checked constructors such as `NaiveDateTime::try_new`
+/// to indicate which field is out of range.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum InvalidDateTime {
+    /// The day is zero, or after the last day of the month in that year.
+    DayOutOfRange,
+    /// The week is zero, or after the last week of the ISO 8601 week-numbering year.
+    WeekOutOfRange,
+    /// The hour is 24 or more.
+    HourOutOfRange,
+    /// The minute is 60 or more.
+    MinuteOutOfRange,
+    /// The second is 60 or more. (Leap seconds are not supported.)
+    SecondOutOfRange,
+    /// The nanosecond is one billion or more.
+    NanosecondOutOfRange,
 }
 
 impl<Tz: fmt::Debug + TimeZone> fmt::Debug for DateTime<Tz> {
@@ -49,45 +143,127 @@ impl<Tz: fmt::Debug + TimeZone> fmt::Debug for DateTime<Tz> {
 
 impl fmt::Debug for NaiveDateTime {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-               self.year, self.month.to_number(), self.day,
-               self.hour, self.minute, self.second)
+        write!(formatter, "{:?} {:?}", self.date(), self.time())
+    }
+}
+
+impl fmt::Debug for NaiveDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:04}-{:02}-{:02}", self.year, self.month.to_number(), self.day)
+    }
+}
+
+impl fmt::Debug for NaiveTime {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        write_fraction_of_second(formatter, self.nanosecond)
     }
 }
 
+/// Write `.` followed by decimal digits, without trailing zeros, or nothing for zero.
+fn write_fraction_of_second(formatter: &mut fmt::Formatter, nanosecond: u32) -> fmt::Result {
+    if nanosecond == 0 {
+        return Ok(())
+    }
+    let mut digits = 9;
+    let mut n = nanosecond;
+    loop {
+        let (quotient, remainder) = (n / 10, n % 10);
+        if remainder != 0 {
+            break
+        }
+        n = quotient;
+        digits -= 1;
+    }
+    write!(formatter, ".{:01$}", n, digits)
+}
+
 impl<Tz: TimeZone> DateTime<Tz> {
     pub fn new(time_zone: Tz, year: i32, month: Month, day: u8,
                hour: u8, minute: u8, second: u8)
                -> Self {
         DateTime {
             naive: NaiveDateTime::new(year, month, day, hour, minute, second),
-            time_zone: time_zone,
+            time_zone,
         }
     }
 
+    /// Like `new`, but return an error if any field is out of range.
+    pub fn try_new(time_zone: Tz, year: i32, month: Month, day: u8,
+                   hour: u8, minute: u8, second: u8)
+                   -> Result<Self, InvalidDateTime> {
+        Ok(DateTime {
+            naive: NaiveDateTime::try_new(year, month, day, hour, minute, second)?,
+            time_zone,
+        })
+    }
+
+    /// Return a copy of this date and time with the given fraction of a second.
+    pub fn with_nanosecond(self, nanosecond: u32) -> Self {
+        DateTime {
+            naive: self.naive.with_nanosecond(nanosecond),
+            time_zone: self.time_zone,
+        }
+    }
+
+    /// Whether the date and time fields are all in range. See `NaiveDateTime::is_valid`.
+    pub fn is_valid(&self) -> bool { self.naive.is_valid() }
+
     pub fn year(&self) -> i32 { self.naive.year }
     pub fn month(&self) -> Month { self.naive.month }
     pub fn day(&self) -> u8 { self.naive.day }
     pub fn hour(&self) -> u8 { self.naive.hour }
     pub fn minute(&self) -> u8 { self.naive.minute }
     pub fn second(&self) -> u8 { self.naive.second }
+    pub fn nanosecond(&self) -> u32 { self.naive.nanosecond }
+    pub fn date(&self) -> NaiveDate { self.naive.date() }
+    pub fn time(&self) -> NaiveTime { self.naive.time() }
 
     pub fn day_of_the_week(&self) -> DayOfTheWeek { self.naive.day_of_the_week() }
 
     pub fn from_timestamp(t: UnixTimestamp, time_zone: Tz) -> Self {
         DateTime {
             naive: time_zone.from_timestamp(t),
-            time_zone: time_zone,
+            time_zone,
+        }
+    }
+
+    pub fn from_timestamp_nanos(t: UnixTimestampNanos, time_zone: Tz) -> Self {
+        DateTime {
+            naive: time_zone.from_timestamp_nanos(t),
+            time_zone,
         }
     }
 
+    /// The fraction of a second, if any, is dropped.
     pub fn to_timestamp(&self) -> Result<UnixTimestamp, LocalTimeConversionError> {
         self.time_zone.to_timestamp(&self.naive)
     }
 
+    pub fn to_timestamp_nanos(&self) -> Result<UnixTimestampNanos, LocalTimeConversionError> {
+        self.time_zone.to_timestamp_nanos(&self.naive)
+    }
+
+    /// The fraction of a second, if any, is dropped.
+    pub fn to_timestamp_with(&self, disambiguation: Disambiguation)
+                             -> Result<UnixTimestamp, LocalTimeConversionError> {
+        self.time_zone.to_timestamp_with(&self.naive, disambiguation)
+    }
+
     pub fn convert_time_zone<NewTz: TimeZone>(&self, new_time_zone: NewTz)
                                               -> Result<DateTime<NewTz>, LocalTimeConversionError> {
-        Ok(DateTime::from_timestamp(try!(self.to_timestamp()), new_time_zone))
+        Ok(DateTime::from_timestamp_nanos(self.to_timestamp_nanos()?, new_time_zone))
+    }
+
+    pub fn convert_time_zone_with<NewTz: TimeZone>(&self, new_time_zone: NewTz,
+                                                   disambiguation: Disambiguation)
+                                                   -> Result<DateTime<NewTz>,
+                                                             LocalTimeConversionError> {
+        let t = UnixTimestampNanos {
+            seconds: self.to_timestamp_with(disambiguation)?.0,
+            nanoseconds: self.naive.nanosecond,
+        };
+        Ok(DateTime::from_timestamp_nanos(t, new_time_zone))
     }
 }
 
@@ -96,8 +272,12 @@ impl<Tz: UnambiguousTimeZone> DateTime<Tz> {
         self.time_zone.to_unambiguous_timestamp(&self.naive)
     }
 
+    pub fn to_unambiguous_timestamp_nanos(&self) -> UnixTimestampNanos {
+        self.time_zone.to_unambiguous_timestamp_nanos(&self.naive)
+    }
+
     pub fn convert_unambiguous_time_zone<NewTz: TimeZone>(&self, new_time_zone: NewTz) -> DateTime<NewTz> {
-        DateTime::from_timestamp(self.to_unambiguous_timestamp(), new_time_zone)
+        DateTime::from_timestamp_nanos(self.to_unambiguous_timestamp_nanos(), new_time_zone)
     }
 }
 
@@ -105,18 +285,177 @@ impl<Tz: UnambiguousTimeZone> DateTime<Tz> {
 impl NaiveDateTime {
     pub fn new(year: i32, month: Month, day: u8, hour: u8, minute: u8, second: u8) -> Self {
         NaiveDateTime {
-            year: year,
-            month: month,
-            day: day,
-            hour: hour,
-            minute: minute,
-            second: second,
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+        }
+    }
+
+    /// Return a copy of this date and time with the given fraction of a second.
+    pub fn with_nanosecond(self, nanosecond: u32) -> Self {
+        NaiveDateTime { nanosecond, .. self }
+    }
+
+    /// Like `new`, but return an error if any field is out of range.
+    ///
+    /// ```rust
+    /// use gregor::{NaiveDateTime, Month, InvalidDateTime};
+    /// assert!(NaiveDateTime::try_new(2016, Month::February, 29, 23, 59, 59).is_ok());
+    /// assert_eq!(NaiveDateTime::try_new(2015, Month::February, 29, 0, 0, 0),
+    ///            Err(InvalidDateTime::DayOutOfRange));
+    /// ```
+    pub fn try_new(year: i32, month: Month, day: u8, hour: u8, minute: u8, second: u8)
+                   -> Result<Self, InvalidDateTime> {
+        let d = NaiveDateTime::new(year, month, day, hour, minute, second);
+        d.check_fields()?;
+        Ok(d)
+    }
+
+    /// Whether the day exists in this month and year,
+    /// and the time is within 00:00:00 to 23:59:59.999999999.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    /// Other methods may panic or return nonsensical results when this returns false.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        self.date().check_fields()?;
+        self.time().check_fields()
+    }
+
+    pub fn from_date_and_time(date: NaiveDate, time: NaiveTime) -> Self {
+        NaiveDateTime {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+            nanosecond: time.nanosecond,
         }
     }
 
+    pub fn date(&self) -> NaiveDate {
+        NaiveDate::new(self.year, self.month, self.day)
+    }
+
+    pub fn time(&self) -> NaiveTime {
+        NaiveTime::new(self.hour, self.minute, self.second).with_nanosecond(self.nanosecond)
+    }
+
+    pub fn day_of_the_week(&self) -> DayOfTheWeek {
+        self.date().day_of_the_week()
+    }
+}
+
+impl NaiveDate {
+    pub fn new(year: i32, month: Month, day: u8) -> Self {
+        NaiveDate { year, month, day }
+    }
+
+    /// Like `new`, but return an error if the day does not exist in this month and year.
+    pub fn try_new(year: i32, month: Month, day: u8) -> Result<Self, InvalidDateTime> {
+        let d = NaiveDate::new(year, month, day);
+        d.check_fields()?;
+        Ok(d)
+    }
+
+    /// Whether the day exists in this month and year.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        if self.day < 1 || self.day > self.month.length(self.year.into()) {
+            Err(InvalidDateTime::DayOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn and_time(self, time: NaiveTime) -> NaiveDateTime {
+        NaiveDateTime::from_date_and_time(self, time)
+    }
+
     pub fn day_of_the_week(&self) -> DayOfTheWeek {
         const JANUARY_1ST_1970: DayOfTheWeek = DayOfTheWeek::Thursday;
-        JANUARY_1ST_1970.add_days(days_since_unix(self))
+        JANUARY_1ST_1970.add_days(self.days_since_unix())
+    }
+
+    /// Day number where 1970-01-01 is day 0, 1970-01-02 is day 1, 1969-12-31 is day -1, etc.
+    ///
+    /// Panics for years beyond about ±5.8 million, where this number does not fit in `i32`.
+    pub fn days_since_unix(&self) -> i32 {
+        days_since_unix(self)
+    }
+
+    /// The inverse of `days_since_unix`. Any `i32` is a valid day number.
+    pub fn from_days_since_unix(days: i32) -> Self {
+        date_from_days_since_unix(days)
+    }
+}
+
+impl NaiveTime {
+    pub fn new(hour: u8, minute: u8, second: u8) -> Self {
+        NaiveTime {
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+        }
+    }
+
+    /// 00:00:00
+    pub fn midnight() -> Self {
+        NaiveTime::new(0, 0, 0)
+    }
+
+    /// Return a copy of this time with the given fraction of a second.
+    pub fn with_nanosecond(self, nanosecond: u32) -> Self {
+        NaiveTime { nanosecond, .. self }
+    }
+
+    /// Like `new`, but return an error if any field is out of range.
+    pub fn try_new(hour: u8, minute: u8, second: u8) -> Result<Self, InvalidDateTime> {
+        let t = NaiveTime::new(hour, minute, second);
+        t.check_fields()?;
+        Ok(t)
+    }
+
+    /// Whether the time is within 00:00:00 to 23:59:59.999999999.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        if self.hour > 23 {
+            Err(InvalidDateTime::HourOutOfRange)
+        } else if self.minute > 59 {
+            Err(InvalidDateTime::MinuteOutOfRange)
+        } else if self.second > 59 {
+            Err(InvalidDateTime::SecondOutOfRange)
+        } else if self.nanosecond >= 1_000_000_000 {
+            Err(InvalidDateTime::NanosecondOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Midnight at the start of this day.
+impl From<NaiveDate> for NaiveDateTime {
+    fn from(date: NaiveDate) -> Self {
+        date.and_time(NaiveTime::midnight())
     }
 }
 
@@ -136,6 +475,18 @@ impl<Tz: UnambiguousTimeZone> From<DateTime<Tz>> for UnixTimestamp {
     }
 }
 
+impl<Tz: Default + TimeZone> From<UnixTimestampNanos> for DateTime<Tz> {
+    fn from(u: UnixTimestampNanos) -> Self {
+        DateTime::from_timestamp_nanos(u, Tz::default())
+    }
+}
+
+impl<Tz: UnambiguousTimeZone> From<DateTime<Tz>> for UnixTimestampNanos {
+    fn from(datetime: DateTime<Tz>) -> Self {
+        datetime.to_unambiguous_timestamp_nanos()
+    }
+}
+
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 pub enum YearKind {
@@ -195,6 +546,15 @@ macro_rules! declare_month {
                 }
             }
 
+            /// Return the English name of this month, such as `"January"`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        Month::$name => stringify!($name),
+                    )+
+                }
+            }
+
             pub fn length(self, year_kind: YearKind) -> u8 {
                 match year_kind {
                     YearKind::Common => match self {
@@ -232,7 +592,7 @@ macro_rules! declare_month {
                 match year_kind {
                     YearKind::Common => match day {
                         $(
-                            $first_day_in_common_years ... $last_day_in_common_years => {
+                            $first_day_in_common_years ..= $last_day_in_common_years => {
                                 (Month::$name, (day - $first_day_in_common_years + 1) as u8)
                             }
                         )+
@@ -240,7 +600,7 @@ macro_rules! declare_month {
                     },
                     YearKind::Leap => match day {
                         $(
-                            $first_day_in_leap_years ... $last_day_in_leap_years => {
+                            $first_day_in_leap_years ..= $last_day_in_leap_years => {
                                 (Month::$name, (day - $first_day_in_leap_years + 1) as u8)
                             }
                         )+
@@ -286,10 +646,19 @@ macro_rules! declare_day_of_the_week {
                 }
             }
 
+            /// Return the English name of this day of the week, such as `"Monday"`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(
+                        DayOfTheWeek::$name => stringify!($name),
+                    )+
+                }
+            }
+
             // What day of the week is it this many days after this day of the week?
             fn add_days(self, days: i32) -> Self {
                 let number = i32::from(self.to_iso_number()) + days;
-                let number = positive_rem((number - 1), 7) + 1;  // Normalize to 1...7
+                let number = positive_rem(number - 1, 7) + 1;  // Normalize to 1...7
                 DayOfTheWeek::from_iso_number(number as u8).unwrap()
             }
         }
@@ -0,0 +1,136 @@
+//! Ordinal dates: a year and a day of the year.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use super::{NaiveDateTime, NaiveDate, DateTime, TimeZone, Month, YearKind, InvalidDateTime};
+use rfc3339::{Parser, ParseError, ParseErrorKind, write_year};
+
+/// A date as a year and a day of the year, such as `2016-198` for 2016-07-16.
+///
+/// `Display` and `FromStr` use the ISO 8601 extended format `YYYY-DDD`,
+/// with the same year representation as `NaiveDate`.
+///
+/// ```rust
+/// use std::convert::TryFrom;
+/// use gregor::{NaiveDate, OrdinalDate};
+/// use gregor::Month::*;
+/// let d: OrdinalDate = "2016-198".parse().unwrap();
+/// assert_eq!(NaiveDate::try_from(d), Ok(NaiveDate::new(2016, July, 16)));
+/// assert_eq!(NaiveDate::new(2016, December, 31).ordinal_date().to_string(), "2016-366");
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+pub struct OrdinalDate {
+    /// Year number per ISO 8601. See `NaiveDateTime::year`.
+    pub year: i32,
+
+    /// January 1st is day 1. December 31st is day 365, or 366 in leap years.
+    pub day_of_year: u16,
+}
+
+impl fmt::Debug for OrdinalDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:04}-{:03}", self.year, self.day_of_year)
+    }
+}
+
+impl OrdinalDate {
+    pub fn new(year: i32, day_of_year: u16) -> Self {
+        OrdinalDate { year, day_of_year }
+    }
+
+    /// Like `new`, but return an error if the day does not exist in this year.
+    pub fn try_new(year: i32, day_of_year: u16) -> Result<Self, InvalidDateTime> {
+        let d = OrdinalDate::new(year, day_of_year);
+        d.check_fields()?;
+        Ok(d)
+    }
+
+    /// Whether the day exists in this year.
+    ///
+    /// Values created with `new` or with a struct literal are not checked.
+    pub fn is_valid(&self) -> bool {
+        self.check_fields().is_ok()
+    }
+
+    fn check_fields(&self) -> Result<(), InvalidDateTime> {
+        let days_in_year = match YearKind::from(self.year) {
+            YearKind::Common => 365,
+            YearKind::Leap => 366,
+        };
+        if self.day_of_year < 1 || self.day_of_year > days_in_year {
+            Err(InvalidDateTime::DayOutOfRange)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl TryFrom<OrdinalDate> for NaiveDate {
+    type Error = InvalidDateTime;
+
+    fn try_from(d: OrdinalDate) -> Result<Self, InvalidDateTime> {
+        d.check_fields()?;
+        let (month, day) = Month::from_day_of_the_year(i32::from(d.day_of_year) - 1,
+                                                       d.year.into());
+        Ok(NaiveDate::new(d.year, month, day))
+    }
+}
+
+/// Midnight at the start of this day.
+impl TryFrom<OrdinalDate> for NaiveDateTime {
+    type Error = InvalidDateTime;
+
+    fn try_from(d: OrdinalDate) -> Result<Self, InvalidDateTime> {
+        NaiveDate::try_from(d).map(NaiveDateTime::from)
+    }
+}
+
+impl NaiveDate {
+    /// The year and day of the year.
+    pub fn ordinal_date(&self) -> OrdinalDate {
+        let day_of_year = self.month.days_since_january_1st(self.year.into())
+                        + i32::from(self.day);
+        OrdinalDate { year: self.year, day_of_year: day_of_year as u16 }
+    }
+}
+
+impl NaiveDateTime {
+    /// The year and day of the year.
+    pub fn ordinal_date(&self) -> OrdinalDate {
+        self.date().ordinal_date()
+    }
+}
+
+impl<Tz: TimeZone> DateTime<Tz> {
+    /// The year and day of the year.
+    pub fn ordinal_date(&self) -> OrdinalDate {
+        self.naive.ordinal_date()
+    }
+}
+
+/// `2016-198`
+impl fmt::Display for OrdinalDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write_year(formatter, self.year)?;
+        write!(formatter, "-{:03}", self.day_of_year)
+    }
+}
+
+/// Parse `2016-198`
+impl FromStr for OrdinalDate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let year = parser.year()?;
+        parser.expect_one_of(b"-")?;
+        let day_position = parser.position;
+        let day_of_year = u16::from(parser.digit()?) * 100 + u16::from(parser.two_digits()?);
+        parser.end()?;
+        OrdinalDate::try_new(year, day_of_year).map_err(|e| ParseError {
+            position: day_position,
+            kind: ParseErrorKind::InvalidDateTime(e),
+        })
+    }
+}
@@ -0,0 +1,352 @@
+//! Time zones described by POSIX `TZ` strings such as `EST5EDT,M3.2.0,M11.1.0`,
+//! as found in the footer of TZif data.
+//!
+//! http://pubs.opengroup.org/onlinepubs/9699919799/basedefs/V1_chap08.html
+
+use core::str::{self, FromStr};
+use super::{NaiveDate, UnixTimestamp, TimeZone, DaylightSaving, Utc, FixedOffsetFromUtc, Month,
+            YearKind, InvalidDateTime};
+use num::positive_rem;
+use rfc3339::{Parser, ParseError, ParseErrorKind};
+use time_zones::{Abbreviation, yearly_dst_change};
+
+/// A time zone described by a POSIX `TZ` string, parsed with `FromStr`.
+///
+/// The string has a standard time abbreviation and offset, optionally followed by
+/// a daylight saving time abbreviation, an optional offset (one hour ahead by default),
+/// and rules for when DST starts and ends in local time.
+/// Rules are `Mm.w.d` (day `d` of week `w` of month `m`, where day 0 is Sunday
+/// and week 5 is the last), `Jn` (day 1 to 365, not counting February 29th)
+/// or `n` (day 0 to 365, counting February 29th),
+/// each optionally followed by `/` and a time between -167 and 167 hours (2 am by default).
+/// Without rules, the United States rules `M3.2.0,M11.1.0` are used.
+///
+/// Offsets are positive west of Greenwich, the opposite of `FixedOffsetFromUtc`.
+/// Abbreviations longer than 16 bytes are accepted but not available from `abbreviation_at`.
+///
+/// ```rust
+/// use gregor::{PosixTz, CentralEurope, DateTime, UnixTimestamp};
+/// let tz: PosixTz = "CET-1CEST,M3.5.0,M10.5.0/3".parse().unwrap();
+/// let t = UnixTimestamp(1468702726);
+/// assert_eq!(DateTime::from_timestamp(t, tz).naive,
+///            DateTime::from_timestamp(t, CentralEurope).naive);
+/// ```
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct PosixTz {
+    std_abbreviation: Option<Abbreviation>,
+    std_offset: FixedOffsetFromUtc,
+    dst: Option<Dst>,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Dst {
+    abbreviation: Option<Abbreviation>,
+    offset: FixedOffsetFromUtc,
+    start: TransitionRule,
+    end: TransitionRule,
+}
+
+/// When daylight saving time starts or ends, in local time.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct TransitionRule {
+    date: RuleDate,
+    /// Seconds since local midnight, between -167 and 167 hours.
+    time: i32,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RuleDate {
+    /// `Jn`: 1 to 365, February 29th is never counted.
+    OneBasedSkippingLeapDay(u16),
+
+    /// `n`: 0 to 365, February 29th is counted in leap years.
+    ZeroBased(u16),
+
+    /// `Mm.w.d`: day `d` (0 for Sunday to 6) of week `w` (1 to 5, 5 is the last) of month `m`.
+    MonthWeekDay { month: Month, week: u8, day: u8 },
+}
+
+const SECONDS_PER_HOUR: i32 = 3600;
+
+/// Used when DST is specified without rules: the United States rules since 2007.
+const DEFAULT_RULES: (TransitionRule, TransitionRule) = (
+    TransitionRule {
+        date: RuleDate::MonthWeekDay { month: Month::March, week: 2, day: 0 },
+        time: 2 * SECONDS_PER_HOUR,
+    },
+    TransitionRule {
+        date: RuleDate::MonthWeekDay { month: Month::November, week: 1, day: 0 },
+        time: 2 * SECONDS_PER_HOUR,
+    },
+);
+
+impl FromStr for PosixTz {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let std_abbreviation = name(&mut parser)?;
+        // POSIX offsets are positive west of Greenwich, the opposite of `FixedOffsetFromUtc`.
+        let std_offset = FixedOffsetFromUtc::from_seconds(-hours_minutes_seconds(&mut parser, 24)?);
+        let dst = if parser.peek().is_some() {
+            let abbreviation = name(&mut parser)?;
+            let offset = match parser.peek() {
+                Some(b',') | None => FixedOffsetFromUtc::from_seconds(
+                    std_offset.seconds_ahead_of_utc() + SECONDS_PER_HOUR),
+                _ => FixedOffsetFromUtc::from_seconds(-hours_minutes_seconds(&mut parser, 24)?),
+            };
+            let (start, end) = if parser.peek().is_some() {
+                parser.expect_one_of(b",")?;
+                let start = transition_rule(&mut parser)?;
+                parser.expect_one_of(b",")?;
+                (start, transition_rule(&mut parser)?)
+            } else {
+                DEFAULT_RULES
+            };
+            Some(Dst { abbreviation, offset, start, end })
+        } else {
+            None
+        };
+        parser.end()?;
+        Ok(PosixTz { std_abbreviation, std_offset, dst })
+    }
+}
+
+/// The same as parsing a footer compiled by `build.rs`, in a `const` context. See `src/tzdb.rs`.
+/// Offsets are in seconds ahead of UTC, the opposite of POSIX.
+#[cfg(feature = "tzdb")]
+pub const fn compiled(std_abbreviation: &str, std_offset: i32, dst: Option<Dst>) -> PosixTz {
+    PosixTz {
+        std_abbreviation: Abbreviation::new(std_abbreviation),
+        std_offset: FixedOffsetFromUtc::from_seconds(std_offset),
+        dst,
+    }
+}
+
+#[cfg(feature = "tzdb")]
+pub const fn compiled_dst(abbreviation: &str, offset: i32, start: TransitionRule,
+                          end: TransitionRule) -> Dst {
+    Dst {
+        abbreviation: Abbreviation::new(abbreviation),
+        offset: FixedOffsetFromUtc::from_seconds(offset),
+        start,
+        end,
+    }
+}
+
+/// `time` is in seconds since local midnight.
+#[cfg(feature = "tzdb")]
+pub const fn compiled_rule(date: RuleDate, time: i32) -> TransitionRule {
+    TransitionRule { date, time }
+}
+
+impl DaylightSaving for PosixTz {
+    fn offset_outside_dst(&self) -> FixedOffsetFromUtc {
+        self.std_offset
+    }
+
+    /// Same as `offset_outside_dst` if there is no DST.
+    fn offset_during_dst(&self) -> FixedOffsetFromUtc {
+        match self.dst {
+            Some(ref dst) => dst.offset,
+            None => self.std_offset,
+        }
+    }
+
+    fn abbreviation_outside_dst(&self) -> Option<&str> {
+        self.std_abbreviation.as_ref().map(Abbreviation::as_str)
+    }
+
+    /// Same as `abbreviation_outside_dst` if there is no DST.
+    fn abbreviation_during_dst(&self) -> Option<&str> {
+        match self.dst {
+            Some(ref dst) => dst.abbreviation.as_ref().map(Abbreviation::as_str),
+            None => self.abbreviation_outside_dst(),
+        }
+    }
+
+    fn is_in_dst(&self, t: UnixTimestamp) -> bool {
+        let dst = match self.dst {
+            Some(ref dst) => dst,
+            None => return false,
+        };
+        let std_offset = i64::from(self.std_offset.seconds_ahead_of_utc());
+        let dst_offset = i64::from(dst.offset.seconds_ahead_of_utc());
+        // DST starts in standard time and ends in daylight saving time.
+        let start_at = |year| dst.start.local_seconds(year) - std_offset;
+        let end_at = |year| dst.end.local_seconds(year) - dst_offset;
+
+        // Transition times up to 167 hours can move a transition into a neighboring year.
+        let year = Utc.from_timestamp(t).year;
+        (year.saturating_sub(1)..=year.saturating_add(1)).any(|year| {
+            let start = start_at(year);
+            let mut end = end_at(year);
+            if end <= start {
+                // Southern hemisphere: DST continues into the next year.
+                end = end_at(year.saturating_add(1))
+            }
+            start <= t.0 && t.0 < end
+        })
+    }
+
+    fn next_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        let dst = self.dst.as_ref()?;
+        yearly_dst_change(self, t, true, |year| dst.start_and_end(year, self.std_offset))
+    }
+
+    fn previous_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        let dst = self.dst.as_ref()?;
+        yearly_dst_change(self, t, false, |year| dst.start_and_end(year, self.std_offset))
+    }
+}
+
+impl Dst {
+    /// When DST starts and ends in `year`, in seconds since 1970-01-01 00:00:00 UTC.
+    fn start_and_end(&self, year: i32, std_offset: FixedOffsetFromUtc) -> [i64; 2] {
+        // DST starts in standard time and ends in daylight saving time.
+        [self.start.local_seconds(year) - i64::from(std_offset.seconds_ahead_of_utc()),
+         self.end.local_seconds(year) - i64::from(self.offset.seconds_ahead_of_utc())]
+    }
+}
+
+impl TransitionRule {
+    /// Seconds since 1970-01-01 00:00:00 in local time.
+    fn local_seconds(&self, year: i32) -> i64 {
+        let year_kind = YearKind::from(year);
+        let day_of_the_year = match self.date {
+            RuleDate::OneBasedSkippingLeapDay(n) => {
+                let n = i32::from(n);
+                match year_kind {
+                    YearKind::Leap if n >= 60 => n,
+                    _ => n - 1,
+                }
+            }
+            RuleDate::ZeroBased(n) => i32::from(n),
+            RuleDate::MonthWeekDay { month, week, day } => {
+                let first = NaiveDate::new(year, month, 1).day_of_the_week().to_iso_number() % 7;
+                let mut day = 1 + positive_rem(i32::from(day) - i32::from(first), 7)
+                            + 7 * (i32::from(week) - 1);
+                while day > i32::from(month.length(year_kind)) {
+                    day -= 7
+                }
+                month.days_since_january_1st(year_kind) + day - 1
+            }
+        };
+        let days = NaiveDate::new(year, Month::January, 1).days_since_unix() + day_of_the_year;
+        i64::from(days) * i64::from(24 * SECONDS_PER_HOUR) + i64::from(self.time)
+    }
+}
+
+/// At least three letters, or at least three letters, digits, `+` or `-` within `<` and `>`.
+fn name(parser: &mut Parser) -> Result<Option<Abbreviation>, ParseError> {
+    let quoted = parser.peek() == Some(b'<');
+    if quoted {
+        parser.position += 1
+    }
+    let start = parser.position;
+    let rest = parser.rest();
+    loop {
+        match parser.peek() {
+            Some(b'a'..=b'z') | Some(b'A'..=b'Z') => {}
+            Some(b'0'..=b'9') | Some(b'+') | Some(b'-') if quoted => {}
+            _ => break,
+        }
+        parser.position += 1
+    }
+    if parser.position - start < 3 {
+        // Report the first unexpected character, or the end of input.
+        parser.next()?;
+        parser.position -= 1;
+        return Err(parser.error(ParseErrorKind::UnexpectedCharacter))
+    }
+    let name = str::from_utf8(&rest[..parser.position - start]).ok().and_then(Abbreviation::new);
+    if quoted {
+        parser.expect_one_of(b">")?;
+    }
+    Ok(name)
+}
+
+/// One to `max_digits` decimal digits.
+fn number(parser: &mut Parser, max_digits: usize) -> Result<i32, ParseError> {
+    let mut n = i32::from(parser.digit()?);
+    for _ in 1..max_digits {
+        match parser.peek() {
+            Some(b'0'..=b'9') => n = n * 10 + i32::from(parser.digit()?),
+            _ => break,
+        }
+    }
+    Ok(n)
+}
+
+/// `[+|-]hh[:mm[:ss]]` in seconds, with up to three digits for hours.
+fn hours_minutes_seconds(parser: &mut Parser, max_hours: i32) -> Result<i32, ParseError> {
+    let start = parser.position;
+    let sign = match parser.peek() {
+        Some(b'+') => { parser.position += 1; 1 }
+        Some(b'-') => { parser.position += 1; -1 }
+        _ => 1,
+    };
+    let hours = number(parser, 3)?;
+    let mut minutes = 0;
+    let mut seconds = 0;
+    if parser.peek() == Some(b':') {
+        parser.position += 1;
+        minutes = number(parser, 2)?;
+        if parser.peek() == Some(b':') {
+            parser.position += 1;
+            seconds = number(parser, 2)?;
+        }
+    }
+    if hours > max_hours || minutes > 59 || seconds > 59 {
+        return Err(ParseError { position: start, kind: ParseErrorKind::OffsetOutOfRange })
+    }
+    Ok(sign * ((hours * 60 + minutes) * 60 + seconds))
+}
+
+/// `date[/time]`
+fn transition_rule(parser: &mut Parser) -> Result<TransitionRule, ParseError> {
+    let start = parser.position;
+    let day_out_of_range = ParseError {
+        position: start,
+        kind: ParseErrorKind::InvalidDateTime(InvalidDateTime::DayOutOfRange),
+    };
+    let date = match parser.peek() {
+        Some(b'J') => {
+            parser.position += 1;
+            let n = number(parser, 3)?;
+            if !(1..=365).contains(&n) {
+                return Err(day_out_of_range)
+            }
+            RuleDate::OneBasedSkippingLeapDay(n as u16)
+        }
+        Some(b'M') => {
+            parser.position += 1;
+            let month = Month::from_number(number(parser, 2)? as u8).ok_or(ParseError {
+                position: start + 1,
+                kind: ParseErrorKind::MonthOutOfRange,
+            })?;
+            parser.expect_one_of(b".")?;
+            let week = parser.digit()?;
+            parser.expect_one_of(b".")?;
+            let day = parser.digit()?;
+            if !(1..=5).contains(&week) || day > 6 {
+                return Err(day_out_of_range)
+            }
+            RuleDate::MonthWeekDay { month, week, day }
+        }
+        _ => {
+            let n = number(parser, 3)?;
+            if n > 365 {
+                return Err(day_out_of_range)
+            }
+            RuleDate::ZeroBased(n as u16)
+        }
+    };
+    let time = if parser.peek() == Some(b'/') {
+        parser.position += 1;
+        hours_minutes_seconds(parser, 167)?
+    } else {
+        2 * SECONDS_PER_HOUR
+    };
+    Ok(TransitionRule { date, time })
+}
@@ -0,0 +1,382 @@
+//! Formatting and parsing per RFC 3339, a profile of ISO 8601.
+//!
+//! https://tools.ietf.org/html/rfc3339
+
+use core::fmt;
+use core::str::FromStr;
+use super::{NaiveDateTime, NaiveDate, NaiveTime, DateTime, TimeZone, UnambiguousTimeZone, Month,
+            Utc, FixedOffsetFromUtc, LocalTimeConversionError, Disambiguation, InvalidDateTime,
+            write_fraction_of_second};
+use time_zones::is_year_in_supported_range;
+
+impl<Tz: TimeZone> DateTime<Tz> {
+    /// The difference between local time and UTC at this date and time.
+    ///
+    /// Returns an error for local times that are ambiguous or do not exist in this time zone.
+    pub fn utc_offset(&self) -> Result<FixedOffsetFromUtc, LocalTimeConversionError> {
+        self.to_timestamp().map(|t| self.time_zone.offset_at(t))
+    }
+}
+
+/// Like `DateTime::utc_offset`, but local times that are ambiguous or do not exist
+/// are resolved with `Disambiguation::Compatible`.
+///
+/// Returns `None` for years outside of the supported range, see `is_year_in_supported_range`.
+pub fn compatible_utc_offset<Tz: TimeZone>(d: &DateTime<Tz>) -> Option<FixedOffsetFromUtc> {
+    if !is_year_in_supported_range(d.naive.year) {
+        return None
+    }
+    let local = Utc.to_unambiguous_timestamp(&d.naive);
+    let utc = d.time_zone.to_timestamp_with(&d.naive, Disambiguation::Compatible).ok()?;
+    Some(FixedOffsetFromUtc::from_seconds((local.0 - utc.0) as i32))
+}
+
+/// Years 0 to 9999 are written with four digits.
+/// Other years use the ISO 8601 expanded representation with a sign and six digits,
+/// such as `+010000` or `-000001`.
+pub fn write_year(formatter: &mut fmt::Formatter, year: i32) -> fmt::Result {
+    if (0..=9999).contains(&year) {
+        write!(formatter, "{:04}", year)
+    } else {
+        write!(formatter, "{:+07}", year)
+    }
+}
+
+/// `2016-07-16`
+impl fmt::Display for NaiveDate {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write_year(formatter, self.year)?;
+        write!(formatter, "-{:02}-{:02}", self.month.to_number(), self.day)
+    }
+}
+
+/// `20:58:46`, with a fraction of a second if it is not zero.
+impl fmt::Display for NaiveTime {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        write_fraction_of_second(formatter, self.nanosecond)
+    }
+}
+
+/// `2016-07-16T20:58:46`, without an offset from UTC.
+impl fmt::Display for NaiveDateTime {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}T{}", self.date(), self.time())
+    }
+}
+
+/// `Z` for UTC, or `+02:00`, or `-05:00`.
+/// Offsets that are not a whole number of minutes are written with seconds,
+/// such as `+00:09:21`, which RFC 3339 does not support.
+impl fmt::Display for FixedOffsetFromUtc {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = self.seconds_ahead_of_utc();
+        if seconds == 0 {
+            return formatter.write_str("Z")
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let seconds = seconds.abs();
+        write!(formatter, "{}{:02}:{:02}", sign, seconds / 3600, seconds / 60 % 60)?;
+        if seconds % 60 != 0 {
+            write!(formatter, ":{:02}", seconds % 60)?;
+        }
+        Ok(())
+    }
+}
+
+/// `2016-07-16T20:58:46+02:00` per RFC 3339,
+/// with the offset from UTC in effect in this time zone at this date and time.
+///
+/// For local times that are ambiguous or do not exist in this time zone,
+/// the offset is that of the instant picked by `Disambiguation::Compatible`:
+/// the earlier offset for repeated local times, and the offset before the gap for skipped ones.
+///
+/// The offset is only supported for years between about -5.8 million and +5.8 million.
+/// For other years, nothing is written and this returns `fmt::Error`,
+/// so that `to_string` panics.
+impl<Tz: TimeZone> fmt::Display for DateTime<Tz> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let offset = compatible_utc_offset(self).ok_or(fmt::Error)?;
+        write!(formatter, "{}{}", self.naive, offset)
+    }
+}
+
+/// Returned when parsing a string fails.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct ParseError {
+    /// Byte offset in the input where the problem was found.
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ParseErrorKind {
+    /// The input ended early.
+    UnexpectedEnd,
+    /// A character other than what is expected at this position, such as a digit or `-`.
+    UnexpectedCharacter,
+    /// A complete value was parsed, but there is more input after it.
+    TrailingCharacters,
+    /// The year is beyond about ±5.8 million, outside of the supported range.
+    YearOutOfRange,
+    /// The month is not between 1 and 12.
+    MonthOutOfRange,
+    /// The day, hour, minute or second is out of range.
+    InvalidDateTime(InvalidDateTime),
+    /// The hours or minutes of the offset from UTC are out of range.
+    OffsetOutOfRange,
+    /// With `parse_from_format`, the pattern contains an unknown `%` directive.
+    UnsupportedDirective,
+    /// With `parse_from_format`, the pattern does not contain enough directives
+    /// to determine the date, or the offset from UTC.
+    MissingField,
+    /// With `parse_from_format`, the number of the day of the week (`%u` or `%w`)
+    /// is out of range.
+    DayOfTheWeekOutOfRange,
+    /// With `parse_from_format`, the parsed day of the week does not match the date.
+    DayOfTheWeekMismatch,
+    /// With `parse_from_format`, the Unix timestamp (`%s`) is beyond the range of `i32` years.
+    TimestampOutOfRange,
+    /// With `parse_from_format`, a date or time field does not match the Unix timestamp (`%s`).
+    TimestampMismatch,
+}
+
+pub struct Parser<'a> {
+    input: &'a [u8],
+    pub position: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Parser { input: input.as_bytes(), position: 0 }
+    }
+
+    pub fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError { position: self.position, kind }
+    }
+
+    /// The part of the input that was not consumed yet.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).cloned()
+    }
+
+    /// Return an error unless the entire input was consumed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error(ParseErrorKind::TrailingCharacters)),
+        }
+    }
+
+    pub fn next(&mut self) -> Result<u8, ParseError> {
+        let byte = self.peek().ok_or_else(|| self.error(ParseErrorKind::UnexpectedEnd))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Consume the next byte if it is one of `expected`.
+    pub fn expect_one_of(&mut self, expected: &[u8]) -> Result<u8, ParseError> {
+        let byte = self.next()?;
+        if expected.contains(&byte) {
+            Ok(byte)
+        } else {
+            self.position -= 1;
+            Err(self.error(ParseErrorKind::UnexpectedCharacter))
+        }
+    }
+
+    pub fn digit(&mut self) -> Result<u8, ParseError> {
+        let byte = self.next()?;
+        match byte {
+            b'0'..=b'9' => Ok(byte - b'0'),
+            _ => {
+                self.position -= 1;
+                Err(self.error(ParseErrorKind::UnexpectedCharacter))
+            }
+        }
+    }
+
+    /// Exactly two decimal digits.
+    pub fn two_digits(&mut self) -> Result<u8, ParseError> {
+        Ok(self.digit()? * 10 + self.digit()?)
+    }
+
+    /// Four digits, or a sign followed by four to nine digits.
+    pub fn year(&mut self) -> Result<i32, ParseError> {
+        let start = self.position;
+        let (sign, min_digits, max_digits) = match self.peek() {
+            Some(b'+') => { self.position += 1; (1, 4, 9) }
+            Some(b'-') => { self.position += 1; (-1, 4, 9) }
+            _ => (1, 4, 4),
+        };
+        let mut year = 0;
+        for _ in 0..min_digits {
+            year = year * 10 + i32::from(self.digit()?)
+        }
+        for _ in min_digits..max_digits {
+            match self.peek() {
+                Some(b'0'..=b'9') => year = year * 10 + i32::from(self.digit()?),
+                _ => break,
+            }
+        }
+        let year = sign * year;
+        if !is_year_in_supported_range(year) {
+            return Err(ParseError { position: start, kind: ParseErrorKind::YearOutOfRange })
+        }
+        Ok(year)
+    }
+
+    pub fn month(&mut self) -> Result<Month, ParseError> {
+        let start = self.position;
+        let number = self.two_digits()?;
+        Month::from_number(number).ok_or(ParseError {
+            position: start,
+            kind: ParseErrorKind::MonthOutOfRange,
+        })
+    }
+
+    /// A `.` followed by one or more digits, if the next character is `.`.
+    /// Digits after the ninth are ignored.
+    pub fn fraction_of_second(&mut self) -> Result<u32, ParseError> {
+        if self.peek() != Some(b'.') {
+            return Ok(0)
+        }
+        self.position += 1;
+        let mut nanosecond = u32::from(self.digit()?) * 100_000_000;
+        let mut unit = 10_000_000;
+        while let Some(b'0'..=b'9') = self.peek() {
+            nanosecond += u32::from(self.digit()?) * unit;
+            unit /= 10;
+        }
+        Ok(nanosecond)
+    }
+
+    /// `2016-07-16`
+    pub fn date(&mut self) -> Result<NaiveDate, ParseError> {
+        let year = self.year()?;
+        self.expect_one_of(b"-")?;
+        let month = self.month()?;
+        self.expect_one_of(b"-")?;
+        let day_position = self.position;
+        let day = self.two_digits()?;
+        let date = NaiveDate::new(year, month, day);
+        date.check_fields().map_err(|e| ParseError {
+            position: day_position,
+            kind: ParseErrorKind::InvalidDateTime(e),
+        })?;
+        Ok(date)
+    }
+
+    /// `20:58:46`, optionally followed by a fraction of a second such as `.123`
+    pub fn time(&mut self) -> Result<NaiveTime, ParseError> {
+        let start = self.position;
+        let hour = self.two_digits()?;
+        self.expect_one_of(b":")?;
+        let minute = self.two_digits()?;
+        self.expect_one_of(b":")?;
+        let second = self.two_digits()?;
+        let time = NaiveTime::new(hour, minute, second).with_nanosecond(self.fraction_of_second()?);
+        time.check_fields().map_err(|e| {
+            let field_position = match e {
+                InvalidDateTime::HourOutOfRange => start,
+                InvalidDateTime::MinuteOutOfRange => start + 3,
+                _ => start + 6,
+            };
+            ParseError {
+                position: field_position,
+                kind: ParseErrorKind::InvalidDateTime(e),
+            }
+        })?;
+        Ok(time)
+    }
+
+    /// Date and time separated by `T`, `t` or a space.
+    pub fn date_time(&mut self) -> Result<NaiveDateTime, ParseError> {
+        let date = self.date()?;
+        self.expect_one_of(b"Tt ")?;
+        Ok(date.and_time(self.time()?))
+    }
+
+    /// `Z`, `z`, or `+hh:mm` or `-hh:mm`
+    pub fn offset(&mut self) -> Result<FixedOffsetFromUtc, ParseError> {
+        let sign = match self.expect_one_of(b"Zz+-")? {
+            b'Z' | b'z' => return Ok(FixedOffsetFromUtc::from_seconds(0)),
+            b'+' => 1,
+            _ => -1,
+        };
+        let start = self.position;
+        let hours = self.two_digits()?;
+        self.expect_one_of(b":")?;
+        let minutes = self.two_digits()?;
+        if hours > 23 || minutes > 59 {
+            return Err(ParseError { position: start, kind: ParseErrorKind::OffsetOutOfRange })
+        }
+        Ok(FixedOffsetFromUtc::from_hours_and_minutes(sign * i32::from(hours),
+                                                      sign * i32::from(minutes)))
+    }
+}
+
+/// Parse `2016-07-16`
+impl FromStr for NaiveDate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let date = parser.date()?;
+        parser.end()?;
+        Ok(date)
+    }
+}
+
+/// Parse `20:58:46` or `20:58:46.123`
+impl FromStr for NaiveTime {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let time = parser.time()?;
+        parser.end()?;
+        Ok(time)
+    }
+}
+
+/// Parse `2016-07-16T20:58:46`, without an offset from UTC.
+///
+/// `t` or a space can be used instead of `T`, and the seconds can have a decimal fraction.
+impl FromStr for NaiveDateTime {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let date_time = parser.date_time()?;
+        parser.end()?;
+        Ok(date_time)
+    }
+}
+
+/// Parse a date and time per RFC 3339,
+/// such as `2016-07-16T20:58:46+02:00` or `2016-07-16T18:58:46.5Z`.
+///
+/// The offset `-00:00` (unknown local offset) is treated like `Z`.
+///
+/// ```rust
+/// use gregor::{DateTime, FixedOffsetFromUtc};
+/// let d: DateTime<FixedOffsetFromUtc> = "2016-07-16T20:58:46+02:00".parse().unwrap();
+/// assert_eq!(d.time_zone, FixedOffsetFromUtc::from_hours_and_minutes(2, 0));
+/// assert_eq!(d.hour(), 20);
+/// ```
+impl FromStr for DateTime<FixedOffsetFromUtc> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s);
+        let naive = parser.date_time()?;
+        let time_zone = parser.offset()?;
+        parser.end()?;
+        Ok(DateTime { naive, time_zone })
+    }
+}
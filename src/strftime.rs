@@ -0,0 +1,230 @@
+//! Formatting with `strftime`-style patterns.
+
+use core::fmt;
+use super::{NaiveDateTime, DateTime, TimeZone, UnambiguousTimeZone, Utc,
+            FixedOffsetFromUtc, Disambiguation, DayOfTheWeek};
+use num::{div_floor, positive_rem};
+use rfc3339::{write_year, compatible_utc_offset};
+use time_zones::Abbreviation;
+
+/// The result of `NaiveDateTime::format` or `DateTime::format`.
+///
+/// This implements `Display`, writing the pattern with each `%` directive replaced:
+///
+/// * `%Y`: year, four digits or ISO 8601 expanded representation as in RFC 3339 formatting
+/// * `%C`: year divided by 100, at least two digits
+/// * `%y`: year modulo 100, two digits
+/// * `%G`, `%g`: like `%Y` and `%y`, for the ISO 8601 week-numbering year
+/// * `%m`: month number, `01` to `12`
+/// * `%b` or `%h`, `%B`: month name, abbreviated or not (`Jan`, `January`)
+/// * `%d`: day of the month, `01` to `31`
+/// * `%e`: day of the month, space-padded (` 1` to `31`)
+/// * `%j`: day of the year, `001` to `366`
+/// * `%a`, `%A`: day of the week name, abbreviated or not (`Mon`, `Monday`)
+/// * `%u`: day of the week number, `1` (Monday) to `7` (Sunday)
+/// * `%w`: day of the week number, `0` (Sunday) to `6` (Saturday)
+/// * `%V`: ISO 8601 week number, `01` to `53`
+/// * `%U`, `%W`: week number of the year, `00` to `53`,
+///   where weeks start on Sunday or Monday respectively
+///   and days before the first Sunday or Monday are in week 0.
+/// * `%H`: hour, `00` to `23`
+/// * `%I`: hour, `01` to `12`
+/// * `%p`: `AM` or `PM`
+/// * `%M`: minute, `00` to `59`
+/// * `%S`: second, `00` to `59`
+/// * `%f`: nanoseconds, nine digits
+/// * `%s`: Unix timestamp (seconds since 1970-01-01 00:00:00 UTC)
+/// * `%z`: offset from UTC, `+hhmm` or `-hhmm`
+/// * `%:z`: offset from UTC, `+hh:mm` or `-hh:mm`
+/// * `%Z`: time zone abbreviation such as `CEST` if known, otherwise the same as `%:z`
+/// * `%F`: same as `%Y-%m-%d`
+/// * `%T`: same as `%H:%M:%S`
+/// * `%R`: same as `%H:%M`
+/// * `%D`: same as `%m/%d/%y`
+/// * `%n`, `%t`, `%%`: newline, tab, and `%`
+///
+/// A `-` flag after `%` disables padding of numbers. For example, `%-d` gives `1` to `31`.
+///
+/// Unknown directives and a trailing `%` are written unchanged,
+/// as are `%s`, `%z`, `%:z` and `%Z` with a `NaiveDateTime` which does not have a time zone.
+/// For a `DateTime` whose local time is ambiguous or does not exist in its time zone,
+/// the offset from UTC is chosen with `Disambiguation::Compatible`, as in `Display`.
+#[derive(Debug, Clone)]
+pub struct Formatted<'a> {
+    naive: NaiveDateTime,
+    /// `None` for `NaiveDateTime`
+    offset: Option<FixedOffsetFromUtc>,
+    abbreviation: Option<Abbreviation>,
+    pattern: &'a str,
+}
+
+impl NaiveDateTime {
+    /// Format with a `strftime`-style pattern. See `Formatted`.
+    ///
+    /// ```rust
+    /// use gregor::NaiveDateTime;
+    /// use gregor::Month::*;
+    /// let d = NaiveDateTime::new(2016, July, 16, 20, 58, 46);
+    /// assert_eq!(d.format("%a, %-d %B %Y %H:%M").to_string(), "Sat, 16 July 2016 20:58");
+    /// ```
+    pub fn format<'a>(&self, pattern: &'a str) -> Formatted<'a> {
+        Formatted {
+            naive: *self,
+            offset: None,
+            abbreviation: None,
+            pattern,
+        }
+    }
+}
+
+impl<Tz: TimeZone> DateTime<Tz> {
+    /// Format with a `strftime`-style pattern. See `Formatted`.
+    pub fn format<'a>(&self, pattern: &'a str) -> Formatted<'a> {
+        // `None` outside of the supported range, where converting to a timestamp would panic.
+        let offset = compatible_utc_offset(self);
+        // For skipped local times, the `Compatible` offset is the one before the gap.
+        // `Earlier` gives an instant with that offset, to find the matching abbreviation.
+        let t = offset.and_then(|_| {
+            self.time_zone.to_timestamp_with(&self.naive, Disambiguation::Earlier).ok()
+        });
+        Formatted {
+            naive: self.naive,
+            offset,
+            abbreviation: t.and_then(|t| self.time_zone.abbreviation_at(t))
+                          .and_then(Abbreviation::new),
+            pattern,
+        }
+    }
+}
+
+impl<'a> Formatted<'a> {
+    /// `literal` is the directive as written in the pattern, used without a time zone.
+    fn write_offset(&self, f: &mut fmt::Formatter, separator: &str, literal: &str)
+                    -> fmt::Result {
+        let seconds = match self.offset {
+            Some(offset) => offset.seconds_ahead_of_utc(),
+            None => return f.write_str(literal),
+        };
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let minutes = seconds.abs() / 60;
+        write!(f, "{}{:02}{}{:02}", sign, minutes / 60, separator, minutes % 60)
+    }
+
+    /// `literal` is the directive as written in the pattern,
+    /// used for unknown directives and for those that need a time zone when there is none.
+    fn write_directive(&self, f: &mut fmt::Formatter, directive: char, pad: bool, literal: &str)
+                       -> fmt::Result {
+        let d = &self.naive;
+        let date = d.date();
+        let number = |f: &mut fmt::Formatter, n: i64, width: usize| {
+            if pad {
+                write!(f, "{:01$}", n, width)
+            } else {
+                write!(f, "{}", n)
+            }
+        };
+        match directive {
+            'Y' => write_year(f, d.year),
+            'C' => number(f, i64::from(div_floor(d.year, 100)), 2),
+            'y' => number(f, i64::from(positive_rem(d.year, 100)), 2),
+            'G' => write_year(f, date.iso_week_date().year),
+            'g' => number(f, i64::from(positive_rem(date.iso_week_date().year, 100)), 2),
+            'm' => number(f, i64::from(d.month.to_number()), 2),
+            'b' | 'h' => f.write_str(&d.month.name()[..3]),
+            'B' => f.write_str(d.month.name()),
+            'd' => number(f, i64::from(d.day), 2),
+            'e' if pad => write!(f, "{:2}", d.day),
+            'e' => write!(f, "{}", d.day),
+            'j' => number(f, i64::from(date.ordinal_date().day_of_year), 3),
+            'a' => f.write_str(&d.day_of_the_week().name()[..3]),
+            'A' => f.write_str(d.day_of_the_week().name()),
+            'u' => write!(f, "{}", d.day_of_the_week().to_iso_number()),
+            'w' => write!(f, "{}", d.day_of_the_week().to_iso_number() % 7),
+            'V' => number(f, i64::from(date.iso_week_date().week), 2),
+            'U' | 'W' => {
+                let first_day_of_the_week = if directive == 'U' {
+                    DayOfTheWeek::Sunday
+                } else {
+                    DayOfTheWeek::Monday
+                };
+                // Days since the start of the week, 0 to 6
+                let weekday = i64::from(d.day_of_the_week().to_iso_number())
+                            - i64::from(first_day_of_the_week.to_iso_number());
+                let day_of_the_year = i64::from(date.ordinal_date().day_of_year) - 1;
+                number(f, (day_of_the_year + 7 - positive_rem(weekday, 7)) / 7, 2)
+            }
+            'H' => number(f, i64::from(d.hour), 2),
+            'I' => number(f, i64::from((d.hour + 11) % 12 + 1), 2),
+            'p' => f.write_str(if d.hour < 12 { "AM" } else { "PM" }),
+            'M' => number(f, i64::from(d.minute), 2),
+            'S' => number(f, i64::from(d.second), 2),
+            'f' => number(f, i64::from(d.nanosecond), 9),
+            's' => match self.offset {
+                Some(offset) => {
+                    let local = Utc.to_unambiguous_timestamp(d).0;
+                    write!(f, "{}", local - i64::from(offset.seconds_ahead_of_utc()))
+                }
+                None => f.write_str(literal),
+            },
+            'z' => self.write_offset(f, "", literal),
+            'Z' => match self.abbreviation {
+                Some(ref abbreviation) => f.write_str(abbreviation.as_str()),
+                None => self.write_offset(f, ":", literal),
+            },
+            'F' => {
+                self.write_directive(f, 'Y', pad, literal)?;
+                write!(f, "-")?;
+                self.write_directive(f, 'm', pad, literal)?;
+                write!(f, "-")?;
+                self.write_directive(f, 'd', pad, literal)
+            }
+            'T' => {
+                self.write_directive(f, 'R', pad, literal)?;
+                write!(f, ":")?;
+                self.write_directive(f, 'S', pad, literal)
+            }
+            'R' => {
+                self.write_directive(f, 'H', pad, literal)?;
+                write!(f, ":")?;
+                self.write_directive(f, 'M', pad, literal)
+            }
+            'D' => {
+                self.write_directive(f, 'm', pad, literal)?;
+                write!(f, "/")?;
+                self.write_directive(f, 'd', pad, literal)?;
+                write!(f, "/")?;
+                self.write_directive(f, 'y', pad, literal)
+            }
+            'n' => f.write_str("\n"),
+            't' => f.write_str("\t"),
+            '%' => f.write_str("%"),
+            _ => f.write_str(literal),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Formatted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut chars = self.pattern.char_indices();
+        while let Some((start, c)) = chars.next() {
+            if c != '%' {
+                write!(f, "{}", c)?;
+                continue
+            }
+            let mut directive = chars.next().map(|(_, c)| c);
+            let flag = directive;
+            if flag == Some('-') || flag == Some(':') {
+                directive = chars.next().map(|(_, c)| c);
+            }
+            let end = self.pattern.len() - chars.as_str().len();
+            let literal = &self.pattern[start..end];
+            match (flag, directive) {
+                (Some(':'), Some('z')) => self.write_offset(f, ":", literal)?,
+                (Some(':'), _) | (_, None) => f.write_str(literal)?,
+                (Some('-'), Some(directive)) => self.write_directive(f, directive, false, literal)?,
+                (_, Some(directive)) => self.write_directive(f, directive, true, literal)?,
+            }
+        }
+        Ok(())
+    }
+}
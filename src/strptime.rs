@@ -0,0 +1,447 @@
+//! Parsing with `strptime`-style patterns.
+
+use core::convert::TryFrom;
+use super::{NaiveDateTime, NaiveDate, NaiveTime, DateTime, TimeZone, Month, DayOfTheWeek,
+            OrdinalDate, FixedOffsetFromUtc, UnixTimestamp, InvalidDateTime};
+use rfc3339::{Parser, ParseErrorKind};
+use time_zones::{is_in_supported_range, is_year_in_supported_range};
+
+/// Returned by `parse_from_format` when parsing fails.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct FormatParseError {
+    /// Byte offset in the input where the problem was found.
+    pub position: usize,
+
+    /// The `%` directive being parsed, such as `'d'` for `%d`,
+    /// or the one that produced an invalid or inconsistent field.
+    /// `None` for literal text in the pattern, trailing input, or missing fields.
+    pub directive: Option<char>,
+
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Copy, Clone)]
+struct Field<T> {
+    value: T,
+    position: usize,
+    directive: char,
+}
+
+#[derive(Default)]
+struct Fields {
+    year: Option<Field<i32>>,
+    month: Option<Field<Month>>,
+    day: Option<Field<u8>>,
+    day_of_the_year: Option<Field<u16>>,
+    day_of_the_week: Option<Field<DayOfTheWeek>>,
+    hour: Option<Field<u8>>,
+    hour_1_to_12: Option<Field<u8>>,
+    pm: Option<Field<bool>>,
+    minute: Option<Field<u8>>,
+    second: Option<Field<u8>>,
+    nanosecond: Option<u32>,
+    offset: Option<FixedOffsetFromUtc>,
+    timestamp: Option<Field<i64>>,
+}
+
+impl NaiveDateTime {
+    /// Parse with a `strptime`-style pattern.
+    ///
+    /// The directives are those documented for `Formatted`,
+    /// except `%C`, `%G`, `%g`, `%U`, `%V`, `%W` and `%Z`.
+    /// Names of months and days of the week are matched case-insensitively,
+    /// and both abbreviated and full names are accepted for `%a`, `%A`, `%b`, `%h` and `%B`.
+    /// `%y` is years 1969 to 2068.
+    /// Whitespace in the pattern matches any amount of whitespace (including none) in the input.
+    ///
+    /// The year and either the month and day or the day of the year (`%j`) are required.
+    /// Hours, minutes, seconds and nanoseconds default to zero.
+    /// A parsed day of the week must match the date.
+    /// An offset from UTC (`%z`) is allowed but ignored.
+    ///
+    /// Alternatively, a Unix timestamp (`%s`) determines the date and time,
+    /// in UTC or at the offset given by `%z`, plus the nanoseconds given by `%f`.
+    /// Other date and time fields are then optional, but must match it.
+    ///
+    /// ```rust
+    /// use gregor::NaiveDateTime;
+    /// use gregor::Month::*;
+    /// assert_eq!(NaiveDateTime::parse_from_format("16/07/2016 20:58", "%d/%m/%Y %H:%M"),
+    ///            Ok(NaiveDateTime::new(2016, July, 16, 20, 58, 0)));
+    /// ```
+    pub fn parse_from_format(input: &str, pattern: &str) -> Result<Self, FormatParseError> {
+        let fields = parse(input, pattern)?;
+        fields.resolve(input.len())
+    }
+}
+
+impl DateTime<FixedOffsetFromUtc> {
+    /// Parse with a `strptime`-style pattern. See `NaiveDateTime::parse_from_format`.
+    ///
+    /// The pattern must include an offset from UTC (`%z`) or a Unix timestamp (`%s`).
+    ///
+    /// ```rust
+    /// use gregor::{DateTime, FixedOffsetFromUtc};
+    /// let d = DateTime::parse_from_format("Sat, 16 Jul 2016 20:58:46 +0200",
+    ///                                     "%a, %d %b %Y %T %z").unwrap();
+    /// assert_eq!(d.time_zone, FixedOffsetFromUtc::from_hours_and_minutes(2, 0));
+    /// ```
+    pub fn parse_from_format(input: &str, pattern: &str) -> Result<Self, FormatParseError> {
+        let mut fields = parse(input, pattern)?;
+        let time_zone = match (fields.offset, fields.timestamp) {
+            (Some(offset), _) => offset,
+            (None, Some(_)) => FixedOffsetFromUtc::from_seconds(0),
+            (None, None) => return Err(FormatParseError {
+                position: input.len(),
+                directive: None,
+                kind: ParseErrorKind::MissingField,
+            }),
+        };
+        fields.offset = Some(time_zone);
+        Ok(DateTime {
+            naive: fields.resolve(input.len())?,
+            time_zone,
+        })
+    }
+}
+
+fn parse(input: &str, pattern: &str) -> Result<Fields, FormatParseError> {
+    let mut parser = Parser::new(input);
+    let mut fields = Fields::default();
+    parse_pattern(&mut parser, &mut fields, pattern)?;
+    parser.end().map_err(|e| FormatParseError {
+        position: e.position,
+        directive: None,
+        kind: e.kind,
+    })?;
+    Ok(fields)
+}
+
+fn parse_pattern(parser: &mut Parser, fields: &mut Fields, pattern: &str)
+                 -> Result<(), FormatParseError> {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let literal_error = |parser: &Parser, kind| FormatParseError {
+            position: parser.position,
+            directive: None,
+            kind,
+        };
+        if c == '%' {
+            let mut directive = chars.next();
+            if directive == Some('-') {
+                directive = chars.next()
+            }
+            if directive == Some(':') {
+                directive = chars.next();
+                if directive != Some('z') {
+                    return Err(literal_error(parser, ParseErrorKind::UnsupportedDirective))
+                }
+            }
+            let directive = directive.ok_or_else(|| {
+                literal_error(parser, ParseErrorKind::UnsupportedDirective)
+            })?;
+            let expansion = match directive {
+                'F' => "%Y-%m-%d",
+                'T' => "%H:%M:%S",
+                'R' => "%H:%M",
+                'D' => "%m/%d/%y",
+                _ => "",
+            };
+            if !expansion.is_empty() {
+                parse_pattern(parser, fields, expansion)?;
+                continue
+            }
+            let position = parser.position;
+            parse_directive(parser, fields, directive).map_err(|kind| FormatParseError {
+                // Running out of input is reported where it happens, other errors at the start.
+                position: if kind == ParseErrorKind::UnexpectedEnd {
+                    parser.position
+                } else {
+                    position
+                },
+                directive: Some(directive),
+                kind,
+            })?
+        } else if c.is_whitespace() {
+            skip_whitespace(parser)
+        } else {
+            let mut buffer = [0; 4];
+            let expected = c.encode_utf8(&mut buffer).as_bytes();
+            if parser.rest().starts_with(expected) {
+                parser.position += expected.len()
+            } else if parser.rest().is_empty() {
+                return Err(literal_error(parser, ParseErrorKind::UnexpectedEnd))
+            } else {
+                return Err(literal_error(parser, ParseErrorKind::UnexpectedCharacter))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn skip_whitespace(parser: &mut Parser) {
+    while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = parser.peek() {
+        parser.position += 1
+    }
+}
+
+/// One to `max_digits` decimal digits.
+fn number(parser: &mut Parser, max_digits: usize) -> Result<i64, ParseErrorKind> {
+    let mut n = i64::from(parser.digit().map_err(|e| e.kind)?);
+    for _ in 1..max_digits {
+        match parser.peek() {
+            Some(b'0'..=b'9') => n = n * 10 + i64::from(parser.digit().map_err(|e| e.kind)?),
+            _ => break,
+        }
+    }
+    Ok(n)
+}
+
+/// A number, possibly preceded by spaces (as produced by `%e`).
+fn small_number(parser: &mut Parser, max_digits: usize) -> Result<u8, ParseErrorKind> {
+    skip_whitespace(parser);
+    Ok(number(parser, max_digits)? as u8)
+}
+
+/// Match a name (case-insensitively) or its three-letter abbreviation.
+fn name<T: Copy>(parser: &mut Parser, candidates: &[T], name: fn(T) -> &'static str)
+                 -> Result<T, ParseErrorKind> {
+    let rest = parser.rest();
+    let matches = |s: &str| {
+        rest.len() >= s.len() && rest[..s.len()].eq_ignore_ascii_case(s.as_bytes())
+    };
+    for &candidate in candidates {
+        let full = name(candidate);
+        for s in &[full, &full[..3]] {
+            if matches(s) {
+                parser.position += s.len();
+                return Ok(candidate)
+            }
+        }
+    }
+    if rest.is_empty() {
+        Err(ParseErrorKind::UnexpectedEnd)
+    } else {
+        Err(ParseErrorKind::UnexpectedCharacter)
+    }
+}
+
+fn offset(parser: &mut Parser) -> Result<FixedOffsetFromUtc, ParseErrorKind> {
+    let sign = match parser.expect_one_of(b"Zz+-").map_err(|e| e.kind)? {
+        b'Z' | b'z' => return Ok(FixedOffsetFromUtc::from_seconds(0)),
+        b'+' => 1,
+        _ => -1,
+    };
+    let hours = parser.two_digits().map_err(|e| e.kind)?;
+    if parser.peek() == Some(b':') {
+        parser.position += 1
+    }
+    let minutes = parser.two_digits().map_err(|e| e.kind)?;
+    if hours > 23 || minutes > 59 {
+        return Err(ParseErrorKind::OffsetOutOfRange)
+    }
+    Ok(FixedOffsetFromUtc::from_hours_and_minutes(sign * i32::from(hours),
+                                                  sign * i32::from(minutes)))
+}
+
+fn parse_directive(parser: &mut Parser, fields: &mut Fields, directive: char)
+                   -> Result<(), ParseErrorKind> {
+    use self::DayOfTheWeek::*;
+    use self::Month::*;
+    const MONTHS: [Month; 12] = [January, February, March, April, May, June, July,
+                                 August, September, October, November, December];
+    const DAYS: [DayOfTheWeek; 7] = [Monday, Tuesday, Wednesday, Thursday, Friday, Saturday,
+                                     Sunday];
+
+    let position = parser.position;
+    macro_rules! field {
+        ($value: expr) => { Some(Field { value: $value, position, directive }) }
+    }
+    match directive {
+        'Y' => {
+            let sign = match parser.peek() {
+                Some(b'+') => { parser.position += 1; 1 }
+                Some(b'-') => { parser.position += 1; -1 }
+                _ => 0,
+            };
+            // Without a sign, only read four digits so that `%Y%m%d` works.
+            let year = number(parser, if sign == 0 { 4 } else { 9 })?;
+            let year = if sign < 0 { -year } else { year } as i32;
+            if !is_year_in_supported_range(year) {
+                return Err(ParseErrorKind::YearOutOfRange)
+            }
+            fields.year = field!(year)
+        }
+        'y' => {
+            let year = i32::from(small_number(parser, 2)?);
+            fields.year = field!(if year < 69 { 2000 + year } else { 1900 + year })
+        }
+        'm' => {
+            let number = small_number(parser, 2)?;
+            let month = Month::from_number(number).ok_or(ParseErrorKind::MonthOutOfRange)?;
+            fields.month = field!(month)
+        }
+        'b' | 'h' | 'B' => fields.month = field!(name(parser, &MONTHS, Month::name)?),
+        'd' | 'e' => fields.day = field!(small_number(parser, 2)?),
+        'j' => {
+            skip_whitespace(parser);
+            fields.day_of_the_year = field!(number(parser, 3)? as u16)
+        }
+        'a' | 'A' => fields.day_of_the_week = field!(name(parser, &DAYS, DayOfTheWeek::name)?),
+        'u' | 'w' => {
+            let number = small_number(parser, 1)?;
+            let number = match (directive, number) {
+                ('w', 0) => 7,
+                ('w', 7) => 8,  // Out of range
+                _ => number,
+            };
+            let day = DayOfTheWeek::from_iso_number(number)
+                .ok_or(ParseErrorKind::DayOfTheWeekOutOfRange)?;
+            fields.day_of_the_week = field!(day)
+        }
+        'H' => fields.hour = field!(small_number(parser, 2)?),
+        'I' => fields.hour_1_to_12 = field!(small_number(parser, 2)?),
+        'p' => {
+            let rest = parser.rest();
+            let pm = if rest.len() >= 2 && rest[..2].eq_ignore_ascii_case(b"AM") {
+                false
+            } else if rest.len() >= 2 && rest[..2].eq_ignore_ascii_case(b"PM") {
+                true
+            } else if rest.len() < 2 {
+                return Err(ParseErrorKind::UnexpectedEnd)
+            } else {
+                return Err(ParseErrorKind::UnexpectedCharacter)
+            };
+            parser.position += 2;
+            fields.pm = field!(pm)
+        }
+        'M' => fields.minute = field!(small_number(parser, 2)?),
+        'S' => fields.second = field!(small_number(parser, 2)?),
+        'f' => {
+            let mut nanosecond = 0;
+            let mut unit = 1_000_000_000;
+            parser.digit().map_err(|e| e.kind)?;
+            parser.position -= 1;
+            while let Some(b'0'..=b'9') = parser.peek() {
+                unit /= 10;
+                nanosecond += u32::from(parser.digit().map_err(|e| e.kind)?) * unit;
+            }
+            fields.nanosecond = Some(nanosecond)
+        }
+        's' => {
+            let negative = parser.peek() == Some(b'-');
+            if negative {
+                parser.position += 1
+            }
+            let seconds = number(parser, 18)?;
+            fields.timestamp = field!(if negative { -seconds } else { seconds })
+        }
+        'z' => fields.offset = Some(offset(parser)?),
+        'n' | 't' => skip_whitespace(parser),
+        '%' => { parser.expect_one_of(b"%").map_err(|e| e.kind)?; }
+        _ => return Err(ParseErrorKind::UnsupportedDirective),
+    }
+    Ok(())
+}
+
+impl Fields {
+    fn resolve(&self, end: usize) -> Result<NaiveDateTime, FormatParseError> {
+        fn error<T>(field: Field<T>, kind: ParseErrorKind) -> FormatParseError {
+            FormatParseError {
+                position: field.position,
+                directive: Some(field.directive),
+                kind,
+            }
+        }
+        let invalid = |e| ParseErrorKind::InvalidDateTime(e);
+        let missing = FormatParseError {
+            position: end,
+            directive: None,
+            kind: ParseErrorKind::MissingField,
+        };
+
+        if let Some(timestamp) = self.timestamp {
+            return self.resolve_timestamp(timestamp)
+        }
+
+        let year = self.year.ok_or(missing)?;
+        let date = match (self.month, self.day, self.day_of_the_year) {
+            (Some(month), Some(day), _) => {
+                NaiveDate::try_new(year.value, month.value, day.value)
+                    .map_err(|e| error(day, invalid(e)))?
+            }
+            (None, None, Some(day_of_the_year)) => {
+                NaiveDate::try_from(OrdinalDate::new(year.value, day_of_the_year.value))
+                    .map_err(|e| error(day_of_the_year, invalid(e)))?
+            }
+            _ => return Err(missing),
+        };
+
+        if let Some(day_of_the_week) = self.day_of_the_week {
+            if day_of_the_week.value != date.day_of_the_week() {
+                return Err(error(day_of_the_week, ParseErrorKind::DayOfTheWeekMismatch))
+            }
+        }
+
+        let hour = match (self.hour, self.hour_1_to_12) {
+            (Some(hour), _) => hour,
+            (None, Some(hour)) => {
+                if hour.value < 1 || hour.value > 12 {
+                    return Err(error(hour, invalid(InvalidDateTime::HourOutOfRange)))
+                }
+                let pm = match self.pm {
+                    Some(Field { value: true, .. }) => 12,
+                    _ => 0,
+                };
+                Field { value: hour.value % 12 + pm, .. hour }
+            }
+            (None, None) => Field { value: 0, position: end, directive: 'H' },
+        };
+        let zero = Field { value: 0, position: end, directive: 'M' };
+        let minute = self.minute.unwrap_or(zero);
+        let second = self.second.unwrap_or(Field { directive: 'S', .. zero });
+        let time = NaiveTime::new(hour.value, minute.value, second.value)
+            .with_nanosecond(self.nanosecond.unwrap_or(0));
+        time.check_fields().map_err(|e| match e {
+            InvalidDateTime::HourOutOfRange => error(hour, invalid(e)),
+            InvalidDateTime::MinuteOutOfRange => error(minute, invalid(e)),
+            _ => error(second, invalid(e)),
+        })?;
+        Ok(date.and_time(time))
+    }
+
+    fn resolve_timestamp(&self, timestamp: Field<i64>) -> Result<NaiveDateTime, FormatParseError> {
+        fn check<T: PartialEq>(field: Option<Field<T>>, value: T) -> Result<(), FormatParseError> {
+            match field {
+                Some(field) if field.value != value => Err(FormatParseError {
+                    position: field.position,
+                    directive: Some(field.directive),
+                    kind: ParseErrorKind::TimestampMismatch,
+                }),
+                _ => Ok(()),
+            }
+        }
+
+        let t = UnixTimestamp(timestamp.value);
+        if !is_in_supported_range(t) {
+            return Err(FormatParseError {
+                position: timestamp.position,
+                directive: Some(timestamp.directive),
+                kind: ParseErrorKind::TimestampOutOfRange,
+            })
+        }
+        let offset = self.offset.unwrap_or_else(|| FixedOffsetFromUtc::from_seconds(0));
+        let d = offset.from_timestamp(t).with_nanosecond(self.nanosecond.unwrap_or(0));
+        check(self.year, d.year)?;
+        check(self.month, d.month)?;
+        check(self.day, d.day)?;
+        check(self.day_of_the_year, d.ordinal_date().day_of_year)?;
+        check(self.day_of_the_week, d.day_of_the_week())?;
+        check(self.hour, d.hour)?;
+        check(self.hour_1_to_12, (d.hour + 11) % 12 + 1)?;
+        check(self.pm, d.hour >= 12)?;
+        check(self.minute, d.minute)?;
+        check(self.second, d.second)?;
+        Ok(d)
+    }
+}
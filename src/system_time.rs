@@ -1,33 +1,58 @@
 use std::time::{Duration as StdDuration, SystemTime, UNIX_EPOCH};
-use super::{UnixTimestamp, DateTime, TimeZone, UnambiguousTimeZone};
+use super::{UnixTimestamp, UnixTimestampNanos, DateTime, TimeZone, UnambiguousTimeZone};
 
+/// Any fraction of a second is rounded towards the past,
+/// consistently with `UnixTimestampNanos::seconds`.
 impl From<SystemTime> for UnixTimestamp {
     fn from(t: SystemTime) -> Self {
-        UnixTimestamp(match t.duration_since(UNIX_EPOCH) {
-            Ok(duration) => duration.as_secs() as i64,
-            Err(error) => -(error.duration().as_secs() as i64)
-        })
+        UnixTimestamp(UnixTimestampNanos::from(t).seconds)
+    }
+}
+
+impl From<SystemTime> for UnixTimestampNanos {
+    fn from(t: SystemTime) -> Self {
+        match t.duration_since(UNIX_EPOCH) {
+            Ok(duration) => UnixTimestampNanos {
+                seconds: duration.as_secs() as i64,
+                nanoseconds: duration.subsec_nanos(),
+            },
+            Err(error) => {
+                let duration = error.duration();
+                let seconds = -(duration.as_secs() as i64);
+                match duration.subsec_nanos() {
+                    0 => UnixTimestampNanos { seconds, nanoseconds: 0 },
+                    n => UnixTimestampNanos { seconds: seconds - 1, nanoseconds: 1_000_000_000 - n },
+                }
+            }
+        }
     }
 }
 
 impl From<UnixTimestamp> for SystemTime {
     fn from(t: UnixTimestamp) -> Self {
-        if t.0 >= 0 {
-            UNIX_EPOCH + StdDuration::from_secs(t.0 as u64)
+        UnixTimestampNanos::from(t).into()
+    }
+}
+
+impl From<UnixTimestampNanos> for SystemTime {
+    fn from(t: UnixTimestampNanos) -> Self {
+        if t.seconds >= 0 {
+            UNIX_EPOCH + StdDuration::new(t.seconds as u64, t.nanoseconds)
         } else {
-            UNIX_EPOCH - StdDuration::from_secs((-t.0) as u64)
+            UNIX_EPOCH - StdDuration::from_secs((-t.seconds) as u64)
+                       + StdDuration::new(0, t.nanoseconds)
         }
     }
 }
 
 impl<Tz: Default + TimeZone> From<SystemTime> for DateTime<Tz> {
     fn from(t: SystemTime) -> Self {
-        UnixTimestamp::from(t).into()
+        UnixTimestampNanos::from(t).into()
     }
 }
 
 impl<Tz: UnambiguousTimeZone> From<DateTime<Tz>> for SystemTime {
     fn from(d: DateTime<Tz>) -> Self {
-        UnixTimestamp::from(d).into()
+        UnixTimestampNanos::from(d).into()
     }
 }
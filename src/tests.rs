@@ -1,4 +1,6 @@
 use super::*;
+use std::fmt::Write;
+use std::string::{String, ToString};
 use time_zones::{days_since_unix, leap_days_since_y0};
 use Month::*;
 use DayOfTheWeek::*;
@@ -9,6 +11,267 @@ fn fmt() {
                "DateTime(Utc, 2016-07-16 20:58:46)");
 }
 
+#[test]
+fn validation() {
+    assert!(NaiveDateTime::try_new(2016, February, 29, 0, 0, 0).is_ok());
+    assert!(NaiveDateTime::try_new(2016, December, 31, 23, 59, 59).is_ok());
+    assert_eq!(NaiveDateTime::try_new(2016, February, 31, 25, 61, 99),
+               Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2015, February, 29, 0, 0, 0),
+               Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2100, February, 29, 0, 0, 0),
+               Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2016, April, 0, 0, 0, 0),
+               Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2016, April, 31, 0, 0, 0),
+               Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2016, April, 30, 24, 0, 0),
+               Err(InvalidDateTime::HourOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2016, April, 30, 23, 60, 0),
+               Err(InvalidDateTime::MinuteOutOfRange));
+    assert_eq!(NaiveDateTime::try_new(2016, April, 30, 23, 59, 60),
+               Err(InvalidDateTime::SecondOutOfRange));
+    assert!(NaiveDateTime::new(2016, April, 30, 23, 59, 59).with_nanosecond(999_999_999).is_valid());
+    assert!(!NaiveDateTime::new(2016, April, 30, 23, 59, 59).with_nanosecond(1_000_000_000).is_valid());
+
+    assert_eq!(DateTime::try_new(Utc, 2016, July, 16, 20, 58, 46),
+               Ok(DateTime::new(Utc, 2016, July, 16, 20, 58, 46)));
+    assert_eq!(DateTime::try_new(Utc, 2016, July, 32, 20, 58, 46),
+               Err(InvalidDateTime::DayOutOfRange));
+
+    let literal = NaiveDateTime {
+        year: 2016,
+        month: June,
+        day: 31,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanosecond: 0,
+    };
+    assert!(!literal.is_valid());
+    assert!(NaiveDateTime { day: 30, .. literal }.is_valid());
+    assert!(!DateTime::new(CentralEurope, 2016, June, 31, 0, 0, 0).is_valid());
+}
+
+#[test]
+fn fmt_nanoseconds() {
+    let d = NaiveDateTime::new(2016, July, 16, 20, 58, 46);
+    assert_eq!(format!("{:?}", d.with_nanosecond(500_000_000)), "2016-07-16 20:58:46.5");
+    assert_eq!(format!("{:?}", d.with_nanosecond(120_000)), "2016-07-16 20:58:46.00012");
+    assert_eq!(format!("{:?}", d.with_nanosecond(1)), "2016-07-16 20:58:46.000000001");
+}
+
+#[test]
+fn rfc3339_display() {
+    assert_eq!(DateTime::new(Utc, 2016, July, 16, 20, 58, 46).to_string(), "2016-07-16T20:58:46Z");
+    assert_eq!(DateTime::new(CentralEurope, 2016, July, 16, 20, 58, 46).to_string(),
+               "2016-07-16T20:58:46+02:00");
+    assert_eq!(DateTime::new(CentralEurope, 2016, January, 16, 20, 58, 46).to_string(),
+               "2016-01-16T20:58:46+01:00");
+    let offset = FixedOffsetFromUtc::from_hours_and_minutes(-3, -30);
+    assert_eq!(DateTime::new(offset, 2016, July, 16, 20, 58, 46).with_nanosecond(120_000_000)
+               .to_string(), "2016-07-16T20:58:46.12-03:30");
+    assert_eq!(FixedOffsetFromUtc::from_seconds(561).to_string(), "+00:09:21");
+    assert!(DateTime::new(CentralEurope, 2016, October, 30, 2, 30, 0).utc_offset().is_err());
+    // Clocks go back from 3am to 2am: the earlier offset is used.
+    let ambiguous = DateTime::new(CentralEurope, 2016, October, 30, 2, 30, 0);
+    assert_eq!(ambiguous.to_string(), "2016-10-30T02:30:00+02:00");
+    assert_eq!(format!("{}", ambiguous), "2016-10-30T02:30:00+02:00");
+    // Clocks go forward from 2am to 3am: this is 3:30am CEST.
+    let nonexistent = DateTime::new(CentralEurope, 2016, March, 27, 2, 30, 0);
+    assert_eq!(nonexistent.to_string(), "2016-03-27T02:30:00+01:00");
+    assert_eq!(nonexistent.to_string().parse::<DateTime<FixedOffsetFromUtc>>().unwrap()
+               .to_timestamp(), nonexistent.to_timestamp_with(Disambiguation::Compatible));
+    assert_eq!(NaiveDateTime::new(2016, July, 16, 20, 58, 46).to_string(), "2016-07-16T20:58:46");
+    assert_eq!(NaiveDate::new(0, January, 1).to_string(), "0000-01-01");
+    assert_eq!(NaiveDate::new(9999, December, 31).to_string(), "9999-12-31");
+    assert_eq!(NaiveDate::new(10000, January, 1).to_string(), "+010000-01-01");
+    assert_eq!(NaiveDate::new(-1, January, 1).to_string(), "-000001-01-01");
+    assert_eq!(NaiveTime::new(9, 5, 0).with_nanosecond(1).to_string(), "09:05:00.000000001");
+
+    let utc = FixedOffsetFromUtc::from_hours_and_minutes(0, 0);
+    assert_eq!(DateTime::new(utc, 5_000_000, January, 1, 0, 0, 0).to_string(),
+               "+5000000-01-01T00:00:00Z");
+    assert_eq!(DateTime::new(CentralEurope, -5_000_000, July, 1, 0, 0, 0).to_string(),
+               "-5000000-07-01T00:00:00+01:00");
+    // Out of the supported range: an error rather than a panic or a truncated timestamp.
+    let mut s = String::new();
+    assert!(write!(s, "{}", DateTime::new(utc, 999_999_999, January, 1, 0, 0, 0)).is_err());
+    assert_eq!(s, "");
+}
+
+#[test]
+fn rfc3339_parse() {
+    fn error(position: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { position, kind }
+    }
+    type Offset = DateTime<FixedOffsetFromUtc>;
+
+    let expected = DateTime::new(FixedOffsetFromUtc::from_hours_and_minutes(2, 0),
+                                 2016, July, 16, 20, 58, 46);
+    assert_eq!("2016-07-16T20:58:46+02:00".parse(), Ok(expected));
+    assert_eq!("2016-07-16t20:58:46+02:00".parse(), Ok(expected));
+    assert_eq!("2016-07-16 20:58:46+02:00".parse(), Ok(expected));
+    assert_eq!("2016-07-16T18:58:46Z".parse::<Offset>().unwrap().convert_time_zone(Utc),
+               expected.convert_time_zone(Utc));
+    assert_eq!("2016-07-16T18:58:46z".parse::<Offset>().unwrap().time_zone,
+               FixedOffsetFromUtc::from_seconds(0));
+    assert_eq!("2016-07-16T20:58:46.5-03:30".parse::<Offset>().unwrap(),
+               DateTime::new(FixedOffsetFromUtc::from_hours_and_minutes(-3, -30),
+                             2016, July, 16, 20, 58, 46).with_nanosecond(500_000_000));
+    assert_eq!("2016-07-16T20:58:46.1234567891Z".parse::<Offset>().unwrap().nanosecond(),
+               123_456_789);
+
+    for s in &["2016-07-16T20:58:46.12-03:30", "+010000-01-01T00:00:00Z", "-000001-12-31T23:59:59Z"] {
+        assert_eq!(s.parse::<Offset>().unwrap().to_string(), *s);
+    }
+
+    assert_eq!("2016-07-16T20:58:46".parse(), Ok(NaiveDateTime::new(2016, July, 16, 20, 58, 46)));
+    assert_eq!("2016-07-16".parse(), Ok(NaiveDate::new(2016, July, 16)));
+    assert_eq!("20:58:46.001".parse(), Ok(NaiveTime::new(20, 58, 46).with_nanosecond(1_000_000)));
+
+    assert_eq!("2016-07-16T20:58:46".parse::<Offset>(), Err(error(19, ParseErrorKind::UnexpectedEnd)));
+    assert_eq!("2016-07-16T20:58:46Z".parse::<NaiveDateTime>(),
+               Err(error(19, ParseErrorKind::TrailingCharacters)));
+    assert_eq!("2016-7-16".parse::<NaiveDate>(), Err(error(6, ParseErrorKind::UnexpectedCharacter)));
+    assert_eq!("2016-13-16".parse::<NaiveDate>(), Err(error(5, ParseErrorKind::MonthOutOfRange)));
+    assert_eq!("+999999999-01-01T00:00:00Z".parse::<Offset>(),
+               Err(error(0, ParseErrorKind::YearOutOfRange)));
+    assert_eq!("-5900000-01-01".parse::<NaiveDate>(),
+               Err(error(0, ParseErrorKind::YearOutOfRange)));
+    assert_eq!("+5800000-01-01".parse(), Ok(NaiveDate::new(5_800_000, January, 1)));
+    assert_eq!("2015-02-29".parse::<NaiveDate>(),
+               Err(error(8, ParseErrorKind::InvalidDateTime(InvalidDateTime::DayOutOfRange))));
+    assert_eq!("2016-07-16T20:60:46Z".parse::<Offset>(),
+               Err(error(14, ParseErrorKind::InvalidDateTime(InvalidDateTime::MinuteOutOfRange))));
+    assert_eq!("2016-07-16T20:58:46.Z".parse::<Offset>(),
+               Err(error(20, ParseErrorKind::UnexpectedCharacter)));
+    assert_eq!("2016-07-16T20:58:46+24:00".parse::<Offset>(),
+               Err(error(20, ParseErrorKind::OffsetOutOfRange)));
+    assert_eq!("2016-07-16T20:58:46+0200".parse::<Offset>(),
+               Err(error(22, ParseErrorKind::UnexpectedCharacter)));
+}
+
+#[test]
+fn strftime() {
+    let d = NaiveDateTime::new(2016, July, 3, 8, 5, 9).with_nanosecond(1_000);
+    assert_eq!(d.format("%Y-%m-%d %H:%M:%S.%f").to_string(), "2016-07-03 08:05:09.000001000");
+    assert_eq!(d.format("%F %T %R %D").to_string(), "2016-07-03 08:05:09 08:05 07/03/16");
+    assert_eq!(d.format("%a %A %b %h %B").to_string(), "Sun Sunday Jul Jul July");
+    assert_eq!(d.format("%-d/%-m/%C%y %e %-H %I%p").to_string(), "3/7/2016  3 8 08AM");
+    assert_eq!(d.format("%j %u %w %U %W %V %G %g").to_string(), "185 7 0 27 26 26 2016 16");
+    assert_eq!(d.format("100%% %n%t").to_string(), "100% \n\t");
+    assert_eq!(NaiveDateTime::new(2016, July, 3, 0, 0, 0).format("%I %p").to_string(), "12 AM");
+    assert_eq!(NaiveDateTime::new(2016, July, 3, 12, 0, 0).format("%I %p").to_string(), "12 PM");
+    assert_eq!(NaiveDateTime::new(12345, July, 3, 12, 0, 0).format("%Y").to_string(), "+012345");
+
+    // ISO weeks around new year
+    let iso = |y, m, d| NaiveDateTime::new(y, m, d, 0, 0, 0).format("%G-W%V-%u").to_string();
+    assert_eq!(iso(2005, January, 1), "2004-W53-6");
+    assert_eq!(iso(2007, January, 1), "2007-W01-1");
+    assert_eq!(iso(2007, December, 30), "2007-W52-7");
+    assert_eq!(iso(2007, December, 31), "2008-W01-1");
+    assert_eq!(iso(2008, December, 28), "2008-W52-7");
+    assert_eq!(iso(2008, December, 29), "2009-W01-1");
+    assert_eq!(iso(2009, December, 31), "2009-W53-4");
+    assert_eq!(iso(2010, January, 3), "2009-W53-7");
+
+    let paris = DateTime::new(CentralEurope, 2016, July, 16, 20, 58, 46);
+    assert_eq!(paris.format("%H:%M %z %:z %Z %s").to_string(), "20:58 +0200 +02:00 CEST 1468695526");
+    let offset = FixedOffsetFromUtc::from_hours_and_minutes(-3, -30);
+    assert_eq!(DateTime::new(offset, 2016, July, 16, 20, 58, 46).format("%z").to_string(), "-0330");
+    let west = DateTime::new(offset, 2016, July, 16, 20, 58, 46);
+    assert_eq!(west.format("%Z").to_string(), "-03:30");
+    assert_eq!(DateTime::new(Utc, 2016, July, 16, 20, 58, 46).format("%Z").to_string(), "UTC");
+
+    // Unknown directives, and those that need a time zone without one, are written unchanged.
+    assert_eq!(d.format("%z %:z %Z %s").to_string(), "%z %:z %Z %s");
+    assert_eq!(d.format("%Q %-Q %:Q %H").to_string(), "%Q %-Q %:Q 08");
+    assert_eq!(d.format("%H%").to_string(), "08%");
+    assert_eq!(d.format("%H%-").to_string(), "08%-");
+    assert_eq!(d.format("%H%:").to_string(), "08%:");
+    assert_eq!(d.format("%é").to_string(), "%é");
+    let ambiguous = DateTime::new(CentralEurope, 2016, October, 30, 2, 30, 0);
+    assert_eq!(ambiguous.format("%H:%M %z %Z %s").to_string(), "02:30 +0200 CEST 1477787400");
+    let nonexistent = DateTime::new(CentralEurope, 2016, March, 27, 2, 30, 0);
+    assert_eq!(nonexistent.format("%H:%M %z %Z %s").to_string(), "02:30 +0100 CET 1459042200");
+}
+
+#[test]
+fn strptime() {
+    fn error(position: usize, directive: Option<char>, kind: ParseErrorKind) -> FormatParseError {
+        FormatParseError { position, directive, kind }
+    }
+    type Offset = DateTime<FixedOffsetFromUtc>;
+    let parse = NaiveDateTime::parse_from_format;
+
+    let expected = NaiveDateTime::new(2016, July, 16, 20, 58, 46);
+    assert_eq!(parse("2016-07-16T20:58:46", "%FT%T"), Ok(expected));
+    assert_eq!(parse("20160716205846", "%Y%m%d%H%M%S"), Ok(expected));
+    assert_eq!(parse("saturday  16 JULY 2016, 8:58:46 pm", "%A %e %B %Y, %I:%M:%S %p"), Ok(expected));
+    assert_eq!(parse("Sat Jul 16 20:58:46 2016", "%a %b %e %T %Y"), Ok(expected));
+    assert_eq!(parse("07/16/16 20:58:46.25", "%D %T.%f"), Ok(expected.with_nanosecond(250_000_000)));
+    assert_eq!(parse("2016-198 20:58:46", "%Y-%j %T"), Ok(expected));
+    assert_eq!(parse("2016-7-16 6 %", "%Y-%-m-%-d %w %%"), Ok(NaiveDateTime::new(2016, July, 16, 0, 0, 0)));
+    assert_eq!(parse("12 AM 1/1/70", "%I %p %m/%d/%y"), Ok(NaiveDateTime::new(1970, January, 1, 0, 0, 0)));
+    assert_eq!(parse("-0001-12-31", "%F"), Ok(NaiveDateTime::new(-1, December, 31, 0, 0, 0)));
+
+    let d = Offset::parse_from_format("Sat, 16 Jul 2016 20:58:46 -0330", "%a, %d %b %Y %T %z").unwrap();
+    assert_eq!(d, DateTime::new(FixedOffsetFromUtc::from_hours_and_minutes(-3, -30),
+                                2016, July, 16, 20, 58, 46));
+    assert_eq!(Offset::parse_from_format("2016-07-16 20:58:46 +02:00", "%F %T %:z").unwrap().time_zone,
+               FixedOffsetFromUtc::from_hours_and_minutes(2, 0));
+    assert_eq!(Offset::parse_from_format("1468702726", "%s").unwrap().naive,
+               NaiveDateTime::new(2016, July, 16, 20, 58, 46));
+    assert_eq!(Offset::parse_from_format("2016-07-16", "%F"),
+               Err(error(10, None, ParseErrorKind::MissingField)));
+
+    assert_eq!(parse("2015-02-29", "%F"),
+               Err(error(8, Some('d'), ParseErrorKind::InvalidDateTime(InvalidDateTime::DayOutOfRange))));
+    assert_eq!(parse("2015-366", "%Y-%j"),
+               Err(error(5, Some('j'), ParseErrorKind::InvalidDateTime(InvalidDateTime::DayOutOfRange))));
+    assert_eq!(parse("Sun 2016-07-16", "%a %F"),
+               Err(error(0, Some('a'), ParseErrorKind::DayOfTheWeekMismatch)));
+    assert_eq!(parse("2016-07-16 24:00", "%F %R"),
+               Err(error(11, Some('H'), ParseErrorKind::InvalidDateTime(InvalidDateTime::HourOutOfRange))));
+    assert_eq!(parse("2016-13-16", "%F"), Err(error(5, Some('m'), ParseErrorKind::MonthOutOfRange)));
+    assert_eq!(parse("Mon +999999999-01-01", "%a %F"),
+               Err(error(4, Some('Y'), ParseErrorKind::YearOutOfRange)));
+    assert_eq!(parse("2016-07-16 7", "%F %w"),
+               Err(error(11, Some('w'), ParseErrorKind::DayOfTheWeekOutOfRange)));
+    assert_eq!(parse("2016-07-16 0", "%F %u"),
+               Err(error(11, Some('u'), ParseErrorKind::DayOfTheWeekOutOfRange)));
+    assert_eq!(parse("2016-07", "%Y-%m"), Err(error(7, None, ParseErrorKind::MissingField)));
+    assert_eq!(parse("2016-07-16", "%Y-%m-%d %H"), Err(error(10, Some('H'), ParseErrorKind::UnexpectedEnd)));
+    assert_eq!(parse("2016-07-16", "%Y/%m/%d"), Err(error(4, None, ParseErrorKind::UnexpectedCharacter)));
+    assert_eq!(parse("2016-07-16x", "%F"), Err(error(10, None, ParseErrorKind::TrailingCharacters)));
+    assert_eq!(parse("Foo 2016", "%b %Y"), Err(error(0, Some('b'), ParseErrorKind::UnexpectedCharacter)));
+    assert_eq!(parse("2016", "%Y %V"), Err(error(4, Some('V'), ParseErrorKind::UnsupportedDirective)));
+    assert_eq!(parse("CEST", "%Z"), Err(error(0, Some('Z'), ParseErrorKind::UnsupportedDirective)));
+
+    // Unix timestamps, with other fields that must match
+    assert_eq!(parse("1468702726.25", "%s.%f"), Ok(expected.with_nanosecond(250_000_000)));
+    assert_eq!(parse("Sat 1468702726 22:58 +0200", "%a %s %R %z"),
+               Ok(NaiveDateTime::new(2016, July, 16, 22, 58, 46)));
+    assert_eq!(parse("1468702726 2016-07-16 08 PM", "%s %F %I %p"), Ok(expected));
+    assert_eq!(parse("1468702726 2016-198", "%s %Y-%j"), Ok(expected));
+    assert_eq!(parse("Sun 1468702726", "%a %s"),
+               Err(error(0, Some('a'), ParseErrorKind::TimestampMismatch)));
+    assert_eq!(parse("1468702726 2015", "%s %Y"),
+               Err(error(11, Some('Y'), ParseErrorKind::TimestampMismatch)));
+    assert_eq!(parse("1468702726 21:58", "%s %R"),
+               Err(error(11, Some('H'), ParseErrorKind::TimestampMismatch)));
+    assert_eq!(parse("1468702726 AM", "%s %p"),
+               Err(error(11, Some('p'), ParseErrorKind::TimestampMismatch)));
+    assert_eq!(parse("999999999999999999", "%s"),
+               Err(error(0, Some('s'), ParseErrorKind::TimestampOutOfRange)));
+    assert_eq!(parse("-999999999999999999", "%s"),
+               Err(error(0, Some('s'), ParseErrorKind::TimestampOutOfRange)));
+    assert_eq!(Offset::parse_from_format("999999999999999999", "%s"),
+               Err(error(0, Some('s'), ParseErrorKind::TimestampOutOfRange)));
+    assert_eq!(parse("-62167219200", "%s"), Ok(NaiveDateTime::new(0, January, 1, 0, 0, 0)));
+}
+
 #[test]
 fn leap_year() {
     assert_eq!(YearKind::from(2010), YearKind::Common);
@@ -71,15 +334,69 @@ fn counting_leap_days() {
 
 #[test]
 fn unixy_days() {
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1969, December, 31, 0, 0, 0)), -1);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1970, January, 1, 0, 0, 0)), 0);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1970, January, 2, 0, 0, 0)), 1);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1970, February, 1, 0, 0, 0)), 31);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1971, January, 1, 0, 0, 0)), 365);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1972, January, 1, 0, 0, 0)), 365 * 2);
+    assert_eq!(days_since_unix(&NaiveDate::new(1969, December, 31)), -1);
+    assert_eq!(days_since_unix(&NaiveDate::new(1970, January, 1)), 0);
+    assert_eq!(days_since_unix(&NaiveDate::new(1970, January, 2)), 1);
+    assert_eq!(days_since_unix(&NaiveDate::new(1970, February, 1)), 31);
+    assert_eq!(days_since_unix(&NaiveDate::new(1971, January, 1)), 365);
+    assert_eq!(days_since_unix(&NaiveDate::new(1972, January, 1)), 365 * 2);
     // 1972 is a leap year.
-    assert_eq!(days_since_unix(&NaiveDateTime::new(1973, January, 1, 0, 0, 0)), 365 * 3 + 1);
-    assert_eq!(days_since_unix(&NaiveDateTime::new(2016, July, 16, 0, 0, 0)), 16998);
+    assert_eq!(days_since_unix(&NaiveDate::new(1973, January, 1)), 365 * 3 + 1);
+    assert_eq!(days_since_unix(&NaiveDate::new(2016, July, 16)), 16998);
+}
+
+#[test]
+fn year_boundaries() {
+    // The year estimated from the day number can be off by one around January 1st.
+    for year in (-10_000..10_000).chain(vec![-5_000_000, -400_001, 400_000, 5_000_000]) {
+        for &d in &[NaiveDateTime::new(year - 1, December, 31, 23, 59, 59),
+                    NaiveDateTime::new(year, January, 1, 0, 0, 0),
+                    NaiveDateTime::new(year, December, 31, 0, 0, 0)] {
+            let t = Utc.to_unambiguous_timestamp(&d);
+            assert_eq!(Utc.from_timestamp(t), d);
+            assert_eq!(NaiveDate::from_days_since_unix(d.date().days_since_unix()), d.date());
+        }
+    }
+}
+
+#[test]
+fn date_and_time() {
+    let d = NaiveDateTime::new(2016, July, 16, 20, 58, 46).with_nanosecond(5);
+    assert_eq!(d.date(), NaiveDate::new(2016, July, 16));
+    assert_eq!(d.time(), NaiveTime::new(20, 58, 46).with_nanosecond(5));
+    assert_eq!(NaiveDateTime::from_date_and_time(d.date(), d.time()), d);
+    assert_eq!(NaiveDate::new(2016, July, 16).and_time(NaiveTime::new(20, 58, 46)),
+               NaiveDateTime::new(2016, July, 16, 20, 58, 46));
+    assert_eq!(NaiveDateTime::from(NaiveDate::new(2016, July, 16)),
+               NaiveDateTime::new(2016, July, 16, 0, 0, 0));
+    assert_eq!(DateTime::new(Utc, 2016, July, 16, 20, 58, 46).date(), NaiveDate::new(2016, July, 16));
+    assert_eq!(format!("{:?}", d.date()), "2016-07-16");
+    assert_eq!(format!("{:?}", d.time()), "20:58:46.000000005");
+
+    assert!(NaiveDate::try_new(2016, February, 29).is_ok());
+    assert_eq!(NaiveDate::try_new(2015, February, 29), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveTime::try_new(23, 60, 0), Err(InvalidDateTime::MinuteOutOfRange));
+    assert!(!NaiveTime::new(9, 0, 0).with_nanosecond(1_000_000_000).is_valid());
+    assert!(NaiveTime::new(9, 0, 0) < NaiveTime::new(17, 30, 0));
+
+    assert_eq!(NaiveDate::new(2016, July, 17).day_of_the_week(), Sunday);
+    assert_eq!(NaiveDate::new(2016, July, 16).days_since_unix(), 16998);
+    for &days in &[-1_000_000, -719_528, -1, 0, 1, 59, 365, 16998, 1_000_000] {
+        assert_eq!(NaiveDate::from_days_since_unix(days).days_since_unix(), days);
+    }
+    assert_eq!(NaiveDate::from_days_since_unix(-1), NaiveDate::new(1969, December, 31));
+    assert_eq!(NaiveDate::from_days_since_unix(-719_528), NaiveDate::new(0, January, 1));
+    assert_eq!(NaiveDate::from_days_since_unix(730), NaiveDate::new(1972, January, 1));
+    assert_eq!(NaiveDate::from_days_since_unix(-2_995_354).days_since_unix(), -2_995_354);
+    for days in -2_000_000..2_000_000 {
+        assert_eq!(NaiveDate::from_days_since_unix(days).days_since_unix(), days);
+    }
+    assert_eq!(NaiveDate::from_days_since_unix(6_000_000), NaiveDate::new(18397, June, 11));
+    assert_eq!(NaiveDate::from_days_since_unix(i32::MAX), NaiveDate::new(5881580, July, 11));
+    assert_eq!(NaiveDate::from_days_since_unix(i32::MIN), NaiveDate::new(-5877641, June, 23));
+    for days in (i32::MIN..=i32::MAX).step_by(99_991).chain(i32::MAX - 1_000..=i32::MAX) {
+        assert_eq!(NaiveDate::from_days_since_unix(days).days_since_unix(), days);
+    }
 }
 
 #[test]
@@ -146,6 +463,175 @@ fn system_time() {
 
     assert_eq!(SystemTime::from(DateTime::new(Utc, 2016, July, 16, 20, 58, 46)),
                UNIX_EPOCH + Duration::from_secs(1_468_702_726));
+
+    let t = UNIX_EPOCH + Duration::new(1_468_702_726, 250_000_000);
+    assert_eq!(DateTime::<Utc>::from(t),
+               DateTime::new(Utc, 2016, July, 16, 20, 58, 46).with_nanosecond(250_000_000));
+    assert_eq!(SystemTime::from(DateTime::<Utc>::from(t)), t);
+
+    let t = UNIX_EPOCH - Duration::new(0, 250_000_000);
+    assert_eq!(UnixTimestampNanos::from(t), UnixTimestampNanos { seconds: -1, nanoseconds: 750_000_000 });
+    assert_eq!(UnixTimestamp::from(t), UnixTimestamp(-1));
+    assert_eq!(SystemTime::from(UnixTimestampNanos::from(t)), t);
+}
+
+#[test]
+fn nanosecond_conversions() {
+    let t = UnixTimestampNanos { seconds: 1_468_702_726, nanoseconds: 123_456_789 };
+    let utc = DateTime::from_timestamp_nanos(t, Utc);
+    assert_eq!(utc, DateTime::new(Utc, 2016, July, 16, 20, 58, 46).with_nanosecond(123_456_789));
+    assert_eq!(utc.to_timestamp_nanos(), Ok(t));
+    assert_eq!(utc.to_timestamp(), Ok(UnixTimestamp(1_468_702_726)));
+    assert_eq!(UnixTimestampNanos::from(utc), t);
+
+    let paris = utc.convert_time_zone(CentralEurope).unwrap();
+    assert_eq!(paris.hour(), 22);
+    assert_eq!(paris.nanosecond(), 123_456_789);
+    assert_eq!(paris.to_timestamp_nanos(), Ok(t));
+
+    let before_epoch = UnixTimestampNanos { seconds: -1, nanoseconds: 500_000_000 };
+    assert_eq!(DateTime::<Utc>::from(before_epoch),
+               DateTime::new(Utc, 1969, December, 31, 23, 59, 59).with_nanosecond(500_000_000));
+
+    assert!(before_epoch < UnixTimestampNanos::from(UnixTimestamp(0)));
+    assert!(UnixTimestampNanos::from(UnixTimestamp(-1)) < before_epoch);
+}
+
+#[test]
+fn durations() {
+    assert_eq!(Duration::from_minutes(90), Duration::from_seconds(5400));
+    assert_eq!(Duration::from_days(1), Duration::from_hours(24));
+    assert_eq!(Duration::from_milliseconds(-1500), Duration::new(-2, 500_000_000));
+    assert_eq!(-Duration::new(1, 250_000_000), Duration::new(-2, 750_000_000));
+    assert_eq!(Duration::from_nanoseconds(-1).seconds(), -1);
+    assert_eq!(Duration::from_nanoseconds(-1).nanoseconds(), 999_999_999);
+    assert_eq!(Duration::from_microseconds(1_500_000) + Duration::from_milliseconds(600),
+               Duration::new(2, 100_000_000));
+    assert!(Duration::from_seconds(-1) < Duration::from_nanoseconds(-1));
+
+    let t = UnixTimestamp(1_468_702_726);
+    assert_eq!(t + Duration::from_minutes(90), UnixTimestamp(1_468_708_126));
+    assert_eq!(t - Duration::from_minutes(90), UnixTimestamp(1_468_697_326));
+    assert_eq!(t + Duration::from_milliseconds(-1), UnixTimestamp(1_468_702_725));
+    assert_eq!(UnixTimestamp(1_468_708_126) - t, Duration::from_minutes(90));
+    assert_eq!(t - UnixTimestamp(1_468_708_126), Duration::from_minutes(-90));
+    assert_eq!(UnixTimestamp(i64::MAX).checked_add(Duration::from_seconds(1)), None);
+    assert_eq!(UnixTimestamp(i64::MIN).checked_sub(Duration::from_seconds(1)), None);
+    assert_eq!(UnixTimestamp(i64::MAX).checked_duration_since(UnixTimestamp(-1)), None);
+
+    let t = UnixTimestampNanos { seconds: 0, nanoseconds: 750_000_000 };
+    assert_eq!(t + Duration::from_milliseconds(500),
+               UnixTimestampNanos { seconds: 1, nanoseconds: 250_000_000 });
+    assert_eq!(t - Duration::from_seconds(1),
+               UnixTimestampNanos { seconds: -1, nanoseconds: 750_000_000 });
+    assert_eq!(UnixTimestampNanos::from(UnixTimestamp(0)) - t, Duration::from_milliseconds(-750));
+
+    let d = DateTime::new(Utc, 2016, December, 31, 23, 0, 0);
+    assert_eq!(d + Duration::from_minutes(90), DateTime::new(Utc, 2017, January, 1, 0, 30, 0));
+    assert_eq!(d - Duration::from_days(366), DateTime::new(Utc, 2015, December, 31, 23, 0, 0));
+
+    // Clocks go forward one hour at 2am local time.
+    let d = DateTime::new(CentralEurope, 2016, March, 27, 1, 30, 0);
+    assert_eq!(d.checked_add(Duration::from_hours(1)),
+               Ok(DateTime::new(CentralEurope, 2016, March, 27, 3, 30, 0)));
+    assert_eq!(DateTime::new(CentralEurope, 2016, March, 27, 3, 30, 0).checked_duration_since(&d),
+               Ok(Duration::from_hours(1)));
+    let nonexistent = DateTime::new(CentralEurope, 2016, March, 27, 2, 30, 0);
+    assert!(matches!(nonexistent.checked_add(Duration::from_hours(1)),
+                     Err(ArithmeticError::LocalTimeConversion(_))));
+    assert_eq!(DateTime::new(Utc, 2016, March, 27, 0, 0, 0)
+               .checked_add(Duration::from_seconds(i64::MAX)),
+               Err(ArithmeticError::Overflow));
+    // In the range of `i64` seconds, but not of `i32` years.
+    let d = DateTime::new(Utc, 2016, July, 1, 0, 0, 0);
+    assert_eq!(d.checked_add(Duration::from_seconds(i64::MAX / 2)), Err(ArithmeticError::Overflow));
+    assert_eq!(d.checked_sub(Duration::from_seconds(i64::MAX / 2)), Err(ArithmeticError::Overflow));
+    let d = DateTime::new(CentralEurope, 2016, July, 1, 0, 0, 0);
+    assert_eq!(d.checked_add(Duration::from_days(2_200_000_000)), Err(ArithmeticError::Overflow));
+    assert_eq!(d.checked_add(Duration::from_days(1_000_000_000)).map(|d| d.year()), Ok(2_739_923));
+}
+
+#[test]
+fn add_months_and_years() {
+    use DayOverflow::*;
+
+    let d = NaiveDateTime::new(2016, January, 31, 12, 30, 0).with_nanosecond(5);
+    let with_day = |year, month, day| NaiveDateTime { year, month, day, .. d };
+    assert_eq!(d.add_months(0, Reject), Ok(d));
+    assert_eq!(d.add_months(1, Clamp), Ok(with_day(2016, February, 29)));
+    assert_eq!(d.add_months(13, Clamp), Ok(with_day(2017, February, 28)));
+    assert_eq!(d.add_months(1, RollOver), Ok(with_day(2016, March, 2)));
+    assert_eq!(d.add_months(13, RollOver), Ok(with_day(2017, March, 3)));
+    assert_eq!(d.add_months(3, RollOver), Ok(with_day(2016, May, 1)));
+    assert_eq!(d.add_months(1, Reject), Err(ArithmeticError::DayOutOfRange));
+    assert_eq!(d.add_months(2, Reject), Ok(with_day(2016, March, 31)));
+    assert_eq!(d.add_months(11, Reject), Ok(with_day(2016, December, 31)));
+    assert_eq!(d.add_months(12, Reject), Ok(with_day(2017, January, 31)));
+    assert_eq!(d.add_months(-1, Reject), Ok(with_day(2015, December, 31)));
+    assert_eq!(d.add_months(-2, Clamp), Ok(with_day(2015, November, 30)));
+    assert_eq!(d.add_months(-12 * 2017, Reject), Ok(with_day(-1, January, 31)));
+    assert_eq!(d.add_months(-12 * 2017 - 1, Reject), Ok(with_day(-2, December, 31)));
+
+    let leap_day = NaiveDateTime::new(2016, February, 29, 0, 0, 0);
+    assert_eq!(leap_day.add_years(4, Reject), Ok(NaiveDateTime::new(2020, February, 29, 0, 0, 0)));
+    assert_eq!(leap_day.add_years(1, Clamp), Ok(NaiveDateTime::new(2017, February, 28, 0, 0, 0)));
+    assert_eq!(leap_day.add_years(-1, RollOver), Ok(NaiveDateTime::new(2015, March, 1, 0, 0, 0)));
+    assert_eq!(leap_day.add_years(84, Reject), Err(ArithmeticError::DayOutOfRange));
+    assert_eq!(leap_day.add_years(i32::MAX, Clamp), Err(ArithmeticError::Overflow));
+    assert_eq!(leap_day.add_years(200_000_000, Reject),
+               Ok(NaiveDateTime::new(200_002_016, February, 29, 0, 0, 0)));
+    assert_eq!(leap_day.add_years(-200_000_000, Reject),
+               Ok(NaiveDateTime::new(-199_997_984, February, 29, 0, 0, 0)));
+    assert_eq!(leap_day.add_years(i32::MAX - 2016, Clamp),
+               Ok(NaiveDateTime::new(i32::MAX, February, 28, 0, 0, 0)));
+    assert_eq!(NaiveDateTime::new(i32::MAX, December, 1, 0, 0, 0).add_months(1, Clamp),
+               Err(ArithmeticError::Overflow));
+
+    let d = DateTime::new(CentralEurope, 2016, August, 31, 9, 0, 0);
+    assert_eq!(d.add_months(1, Clamp), Ok(DateTime::new(CentralEurope, 2016, September, 30, 9, 0, 0)));
+    assert_eq!(d.add_years(-1, Reject), Ok(DateTime::new(CentralEurope, 2015, August, 31, 9, 0, 0)));
+}
+
+#[test]
+fn differences() {
+    fn difference(years: i64, months: i64, days: i64, hours: i64, minutes: i64, seconds: i64)
+                  -> CalendarDifference {
+        CalendarDifference { years, months, days, hours, minutes, seconds, nanoseconds: 0 }
+    }
+
+    let d = |y, m, d| NaiveDate::new(y, m, d);
+    assert_eq!(d(2016, July, 16).days_since(&d(1970, January, 1)), 16998);
+    assert_eq!(d(1970, January, 1).days_since(&d(2016, July, 16)), -16998);
+    assert_eq!(d(2016, March, 1).days_since(&d(2016, February, 28)), 2);
+
+    assert_eq!(d(2016, July, 16).calendar_difference_since(&d(1990, August, 31)),
+               difference(25, 10, 16, 0, 0, 0));
+    assert_eq!(d(2015, February, 28).calendar_difference_since(&d(2015, January, 31)),
+               difference(0, 1, 0, 0, 0, 0));
+    assert_eq!(d(2015, March, 1).calendar_difference_since(&d(2015, January, 31)),
+               difference(0, 1, 1, 0, 0, 0));
+    assert_eq!(d(2017, February, 28).calendar_difference_since(&d(2016, February, 29)),
+               difference(1, 0, 0, 0, 0, 0));
+    assert_eq!(d(2015, February, 28).calendar_difference_since(&d(2015, March, 31)),
+               difference(0, -1, 0, 0, 0, 0));
+    assert_eq!(d(2015, January, 30).calendar_difference_since(&d(2016, March, 1)),
+               difference(-1, -1, -2, 0, 0, 0));
+    assert_eq!(d(2016, July, 16).calendar_difference_since(&d(2016, July, 16)),
+               CalendarDifference::default());
+
+    let dt = NaiveDateTime::new;
+    assert_eq!(dt(2016, July, 16, 1, 0, 0).days_since(&dt(2016, July, 15, 2, 0, 0)), 0);
+    assert_eq!(dt(2016, July, 16, 2, 0, 0).days_since(&dt(2016, July, 15, 2, 0, 0)), 1);
+    assert_eq!(dt(2016, July, 15, 1, 0, 0).days_since(&dt(2016, July, 16, 2, 0, 0)), -1);
+    assert_eq!(dt(2016, August, 1, 1, 0, 0).calendar_difference_since(&dt(2016, July, 31, 2, 0, 0)),
+               difference(0, 0, 0, 23, 0, 0));
+    assert_eq!(dt(2016, August, 31, 1, 0, 0).calendar_difference_since(&dt(2016, July, 31, 2, 0, 0)),
+               difference(0, 0, 30, 23, 0, 0));
+    assert_eq!(dt(2016, July, 31, 2, 0, 0).calendar_difference_since(&dt(2016, August, 31, 1, 0, 0)),
+               difference(0, 0, -30, -23, 0, 0));
+    assert_eq!(dt(2016, July, 16, 20, 58, 46).with_nanosecond(5)
+               .calendar_difference_since(&dt(2015, July, 16, 21, 0, 0)),
+               CalendarDifference { nanoseconds: 5, .. difference(0, 11, 29, 23, 58, 46) });
 }
 
 #[test]
@@ -198,9 +684,267 @@ fn day_of_the_week() {
     assert_eq!(NaiveDateTime::new(-4713, November, 24, 0, 0, 0).day_of_the_week(), Monday);
 }
 
+#[test]
+fn iso_week_date() {
+    let week_date = |y, m, d| NaiveDate::new(y, m, d).iso_week_date();
+    assert_eq!(week_date(2005, January, 1), IsoWeekDate::new(2004, 53, Saturday));
+    assert_eq!(week_date(2007, January, 1), IsoWeekDate::new(2007, 1, Monday));
+    assert_eq!(week_date(2007, December, 31), IsoWeekDate::new(2008, 1, Monday));
+    assert_eq!(week_date(2008, December, 29), IsoWeekDate::new(2009, 1, Monday));
+    assert_eq!(week_date(2010, January, 3), IsoWeekDate::new(2009, 53, Sunday));
+    assert_eq!(week_date(2016, July, 16), IsoWeekDate::new(2016, 28, Saturday));
+    assert_eq!(NaiveDateTime::new(2016, July, 16, 20, 58, 0).iso_week_date().week, 28);
+    assert_eq!(format!("{:?}", week_date(2010, January, 3)), "2009-W53-7");
+
+    assert_eq!(NaiveDate::from(IsoWeekDate::new(2009, 1, Monday)),
+               NaiveDate::new(2008, December, 29));
+    assert_eq!(NaiveDate::from(IsoWeekDate::new(2004, 53, Saturday)),
+               NaiveDate::new(2005, January, 1));
+    for days in -200_000..200_000 {
+        let date = NaiveDate::from_days_since_unix(days);
+        let week_date = date.iso_week_date();
+        assert!(week_date.is_valid());
+        assert_eq!(week_date.weekday, date.day_of_the_week());
+        assert_eq!(NaiveDate::from(week_date), date);
+    }
+
+    let long_years: std::vec::Vec<_> = (2000..2030).filter(|&y| IsoWeekDate::weeks_in_year(y) == 53)
+                                                   .collect();
+    assert_eq!(long_years, [2004, 2009, 2015, 2020, 2026]);
+    assert_eq!(IsoWeekDate::try_new(2015, 53, Sunday), Ok(IsoWeekDate::new(2015, 53, Sunday)));
+    assert_eq!(IsoWeekDate::try_new(2016, 53, Sunday), Err(InvalidDateTime::WeekOutOfRange));
+    assert_eq!(IsoWeekDate::try_new(2016, 0, Sunday), Err(InvalidDateTime::WeekOutOfRange));
+}
+
+#[test]
+fn ordinal_date() {
+    use core::convert::TryFrom;
+    assert_eq!(NaiveDate::new(2016, July, 16).ordinal_date(), OrdinalDate::new(2016, 198));
+    assert_eq!(NaiveDate::new(2015, December, 31).ordinal_date(), OrdinalDate::new(2015, 365));
+    assert_eq!(NaiveDateTime::new(2016, March, 1, 12, 0, 0).ordinal_date().day_of_year, 61);
+    let date = |y, d| NaiveDate::try_from(OrdinalDate::new(y, d));
+    assert_eq!(date(2016, 366), Ok(NaiveDate::new(2016, December, 31)));
+    assert_eq!(date(2015, 366), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(date(2016, 0), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(date(2016, 999), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(NaiveDateTime::try_from(OrdinalDate::new(2016, 1)),
+               Ok(NaiveDateTime::new(2016, January, 1, 0, 0, 0)));
+    assert_eq!(OrdinalDate::try_new(2000, 366), Ok(OrdinalDate::new(2000, 366)));
+    assert_eq!(OrdinalDate::try_new(1900, 366), Err(InvalidDateTime::DayOutOfRange));
+    assert!(!OrdinalDate::new(1900, 366).is_valid());
+    for days in -200_000..200_000 {
+        let date = NaiveDate::from_days_since_unix(days);
+        assert_eq!(NaiveDate::try_from(date.ordinal_date()), Ok(date));
+    }
+
+    assert_eq!(OrdinalDate::new(2016, 7).to_string(), "2016-007");
+    assert_eq!(OrdinalDate::new(12345, 42).to_string(), "+012345-042");
+    assert_eq!(format!("{:?}", OrdinalDate::new(2016, 7)), "2016-007");
+    assert_eq!("2016-007".parse(), Ok(OrdinalDate::new(2016, 7)));
+    assert_eq!("-000001-365".parse(), Ok(OrdinalDate::new(-1, 365)));
+    assert_eq!("2015-366".parse::<OrdinalDate>(), Err(ParseError {
+        position: 5,
+        kind: ParseErrorKind::InvalidDateTime(InvalidDateTime::DayOutOfRange),
+    }));
+    assert_eq!("2016-07".parse::<OrdinalDate>(),
+               Err(ParseError { position: 7, kind: ParseErrorKind::UnexpectedEnd }));
+    assert_eq!("2016-07-16".parse::<OrdinalDate>(),
+               Err(ParseError { position: 7, kind: ParseErrorKind::UnexpectedCharacter }));
+}
+
+#[test]
+fn julian_day() {
+    assert_eq!(NaiveDate::new(-4713, November, 24).julian_day_number(), 0);
+    assert_eq!(NaiveDate::new(2000, January, 1).julian_day_number(), 2_451_545);
+    assert_eq!(NaiveDate::from_julian_day_number(2_457_586), Some(NaiveDate::new(2016, July, 16)));
+    assert_eq!(NaiveDate::new(1858, November, 17).modified_julian_day(), 0);
+    assert_eq!(NaiveDate::new(2016, July, 16).modified_julian_day(), 57_585);
+    assert_eq!(NaiveDate::from_modified_julian_day(-1), Some(NaiveDate::new(1858, November, 16)));
+    assert_eq!(NaiveDate::new(1, January, 1).rata_die(), 1);
+    assert_eq!(NaiveDate::new(1970, January, 1).rata_die(), 719_163);
+    assert_eq!(NaiveDate::from_rata_die(0), Some(NaiveDate::new(0, December, 31)));
+    let days = (-2_000_000..2_000_000).step_by(997)
+        .chain(vec![i32::MIN, i32::MIN + 1, -1_000_000_000, 1_000_000_000, i32::MAX - 1, i32::MAX]);
+    for days in days {
+        let date = NaiveDate::from_days_since_unix(days);
+        assert_eq!(NaiveDate::from_julian_day_number(date.julian_day_number()), Some(date));
+        assert_eq!(NaiveDate::from_modified_julian_day(date.modified_julian_day()), Some(date));
+        assert_eq!(NaiveDate::from_rata_die(date.rata_die()), Some(date));
+        assert_eq!(date.julian_day_number() - date.modified_julian_day(), 2_400_001);
+    }
+    assert_eq!(NaiveDate::from_julian_day_number(10_000_000),
+               Some(NaiveDate::new(22_666, December, 20)));
+    assert_eq!(NaiveDate::from_rata_die(i64::from(i32::MAX)),
+               Some(NaiveDate::from_days_since_unix(i32::MAX - 719_163)));
+    assert_eq!(NaiveDate::from_rata_die(i64::from(i32::MAX) + 719_164), None);
+    assert_eq!(NaiveDate::from_julian_day_number(i64::from(i32::MIN) + 2_440_587), None);
+    assert_eq!(NaiveDate::from_modified_julian_day(i64::MIN), None);
+    assert_eq!(NaiveDate::from_modified_julian_day(i64::MAX), None);
+
+    // J2000.0
+    let j2000 = UnixTimestamp::from(DateTime::new(Utc, 2000, January, 1, 12, 0, 0));
+    assert_eq!(j2000.julian_date(), 2_451_545.0);
+    assert_eq!(j2000.modified_julian_date(), 51_544.5);
+    assert_eq!(UnixTimestamp::from_julian_date(2_451_545.0), Some(j2000));
+    assert_eq!(UnixTimestamp::from_modified_julian_date(51_544.5), Some(j2000));
+    assert_eq!(UnixTimestamp(-43_200).modified_julian_date(), 40_586.5);
+    assert_eq!(UnixTimestamp::from_modified_julian_date(40_586.25), Some(UnixTimestamp(-64_800)));
+    assert_eq!(UnixTimestamp::from_julian_date(0.0),
+               Some(UnixTimestamp::from(DateTime::new(Utc, -4713, November, 24, 12, 0, 0))));
+
+    let t = UnixTimestampNanos { seconds: -1, nanoseconds: 500_000_000 };
+    assert_eq!(t.modified_julian_date(), 40_587.0 - 0.5 / 86_400.0);
+    let t = UnixTimestampNanos { seconds: 1_468_702_726, nanoseconds: 250_000_000 };
+    let back = UnixTimestampNanos::from_julian_date(t.julian_date()).unwrap();
+    assert_eq!(back.seconds, t.seconds);
+    assert!((i64::from(back.nanoseconds) - 250_000_000).abs() < 100_000);
+    let back = UnixTimestampNanos::from_modified_julian_date(t.modified_julian_date()).unwrap();
+    assert_eq!(back.seconds, t.seconds);
+    assert!((i64::from(back.nanoseconds) - 250_000_000).abs() < 10_000);
+    for seconds in (-10_000_000_000..10_000_000_000).step_by(9_999_991) {
+        let t = UnixTimestamp(seconds);
+        assert_eq!(UnixTimestamp::from_julian_date(t.julian_date()), Some(t));
+        assert_eq!(UnixTimestamp::from_modified_julian_date(t.modified_julian_date()), Some(t));
+    }
+
+    // Out of range
+    for &jd in &[1e300, -1e300, f64::NAN, f64::INFINITY,
+                 f64::NEG_INFINITY, 2_440_587.5 + 2.2e9, 2_440_587.5 - 2.2e9] {
+        assert_eq!(UnixTimestamp::from_julian_date(jd), None);
+        assert_eq!(UnixTimestamp::from_modified_julian_date(jd), None);
+        assert_eq!(UnixTimestampNanos::from_julian_date(jd), None);
+        assert_eq!(NaiveDateTime::from_julian_date(jd), None);
+        assert_eq!(NaiveDateTime::from_modified_julian_date(jd), None);
+    }
+    // `f64` is precise to about 40 milliseconds that far from the epoch.
+    let max = NaiveDate::from_days_since_unix(i32::MAX - 1).and_time(NaiveTime::new(23, 0, 0));
+    let back = NaiveDateTime::from_julian_date(max.julian_date()).unwrap();
+    assert_eq!(back.with_nanosecond(0), max);
+    let max = Utc.to_unambiguous_timestamp(&max);
+    assert_eq!(UnixTimestamp::from_julian_date(max.julian_date()), Some(max));
+    let min = NaiveDate::from_days_since_unix(i32::MIN);
+    assert_eq!(NaiveDateTime::from_modified_julian_date(min.modified_julian_day() as f64),
+               Some(min.into()));
+
+    let d = NaiveDateTime::new(2016, July, 16, 20, 58, 46).with_nanosecond(500_000_000);
+    assert_eq!(d.modified_julian_date(), 57_585.0 + (75_526.5 / 86_400.0));
+    let back = NaiveDateTime::from_modified_julian_date(d.modified_julian_date()).unwrap();
+    assert_eq!(back.with_nanosecond(0), d.with_nanosecond(0));
+    assert!((i64::from(back.nanosecond) - 500_000_000).abs() < 10_000);
+    assert_eq!(NaiveDateTime::from_julian_date(2_457_586.0),
+               Some(NaiveDateTime::new(2016, July, 16, 12, 0, 0)));
+}
+
+#[test]
+fn julian_calendar() {
+    assert_eq!(JulianDate::from(NaiveDate::new(1970, January, 1)),
+               JulianDate::new(1969, December, 19));
+    assert_eq!(JulianDate::new(1969, December, 19).days_since_unix(), 0);
+    assert_eq!(NaiveDate::from(JulianDate::new(1, January, 3)), NaiveDate::new(1, January, 1));
+    assert_eq!(NaiveDate::from(JulianDate::new(-4712, January, 1)).julian_day_number(), 0);
+    assert_eq!(NaiveDate::from(JulianDate::new(1582, October, 4)),
+               NaiveDate::new(1582, October, 14));
+    assert_eq!(NaiveDate::from(JulianDate::new(1900, February, 29)),
+               NaiveDate::new(1900, March, 13));
+    assert_eq!(JulianDate::new(1582, October, 4).day_of_the_week(), Thursday);
+    assert_eq!(NaiveDateTime::from(JulianDate::new(1582, October, 5)),
+               NaiveDateTime::new(1582, October, 15, 0, 0, 0));
+    assert!(JulianDate::try_new(1900, February, 29).is_ok());
+    assert!(JulianDate::try_new(-1, February, 29).is_err());
+    assert_eq!(JulianDate::try_new(1901, February, 29), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(format!("{:?}", JulianDate::new(1582, October, 4)), "1582-10-04 (Julian)");
+    let days = (-2_000_000..2_000_000).step_by(997)
+        .chain(vec![i32::MIN, i32::MIN + 1, -1_000_000_000, 1_000_000_000, i32::MAX - 1, i32::MAX]);
+    for days in days {
+        let date = JulianDate::from_days_since_unix(days);
+        assert!(date.is_valid());
+        assert_eq!(date.days_since_unix(), days);
+        assert_eq!(JulianDate::from(NaiveDate::from_days_since_unix(days)), date);
+        assert_eq!(NaiveDate::from(date), NaiveDate::from_days_since_unix(days));
+    }
+    assert_eq!(JulianDate::from_days_since_unix(i32::MAX), JulianDate::new(5_881_459, October, 5));
+    assert_eq!(JulianDate::from_days_since_unix(i32::MIN), JulianDate::new(-5_877_520, March, 3));
+    assert_eq!(JulianDate::from(NaiveDateTime::new(1643, January, 4, 23, 59, 59)),
+               JulianDate::new(1642, December, 25));
+
+    let calendar = HistoricalCalendar::default();
+    assert_eq!(calendar, HistoricalCalendar::GREGORIAN_REFORM);
+    assert_eq!(calendar.month_length(1582, September), 30);
+    assert_eq!(calendar.month_length(1582, October), 21);
+    assert_eq!(calendar.month_length(1500, February), 29);
+    assert_eq!(calendar.month_length(1700, February), 28);
+    assert_eq!(calendar.date(1582, October, 4),
+               Ok(HistoricalDate::Julian(JulianDate::new(1582, October, 4))));
+    assert_eq!(calendar.date(1582, October, 5), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(calendar.date(1582, October, 14), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(calendar.date(1582, October, 15),
+               Ok(HistoricalDate::Gregorian(NaiveDate::new(1582, October, 15))));
+    assert_eq!(calendar.date(1582, October, 32), Err(InvalidDateTime::DayOutOfRange));
+    let d = calendar.from_gregorian(NaiveDate::new(1582, October, 14));
+    assert_eq!((d.year(), d.month(), d.day()), (1582, October, 4));
+    assert_eq!(NaiveDate::from(d), NaiveDate::new(1582, October, 14));
+    let d = calendar.from_gregorian(NaiveDate::new(1582, October, 15));
+    assert_eq!(d, HistoricalDate::Gregorian(NaiveDate::new(1582, October, 15)));
+
+    let calendar = HistoricalCalendar::GREAT_BRITAIN;
+    assert_eq!(calendar.month_length(1752, September), 19);
+    assert_eq!(calendar.month_length(1700, February), 29);
+    assert!(calendar.date(1752, September, 2).is_ok());
+    assert!(calendar.date(1752, September, 3).is_err());
+    assert!(calendar.date(1752, September, 13).is_err());
+    assert!(calendar.date(1752, September, 14).is_ok());
+
+    // Russia: 1918-01-31 (Julian) was followed by 1918-02-14
+    let calendar = HistoricalCalendar::new(NaiveDate::new(1918, February, 14));
+    assert_eq!(calendar.month_length(1918, January), 31);
+    assert_eq!(calendar.month_length(1918, February), 15);
+    for days in (-1_000_000..1_000_000).step_by(97) {
+        let gregorian = NaiveDate::from_days_since_unix(days);
+        let d = calendar.from_gregorian(gregorian);
+        assert_eq!(calendar.date(d.year(), d.month(), d.day()), Ok(d));
+        assert_eq!(NaiveDate::from(d), gregorian);
+    }
+}
+
+#[test]
+fn calendar_trait() {
+    fn round_trip<C: Calendar>(calendar: &C) where C::Date: PartialEq {
+        for days in (-1_000_000..1_000_000).step_by(997) {
+            let date = calendar.date_from_days_since_unix(days);
+            assert_eq!(calendar.days_since_unix(&date), days);
+            assert!(calendar.convert(&date, calendar) == date);
+            assert_eq!(calendar.convert(&date, &Gregorian), NaiveDate::from_days_since_unix(days));
+        }
+    }
+    round_trip(&Gregorian);
+    round_trip(&Julian);
+    round_trip(&HistoricalCalendar::GREAT_BRITAIN);
+
+    assert_eq!(Gregorian.year_kind(1900), YearKind::Common);
+    assert_eq!(Julian.year_kind(1900), YearKind::Leap);
+    assert_eq!(Gregorian.month_length(2000, February), 29);
+    assert_eq!(Julian.month_length(-1, February), 28);
+    assert_eq!(Gregorian.date(2016, February, 30), Err(InvalidDateTime::DayOutOfRange));
+    assert_eq!(Julian.date(1900, February, 29), Ok(JulianDate::new(1900, February, 29)));
+    assert_eq!(Julian.convert(&JulianDate::new(1969, December, 19), &Gregorian),
+               NaiveDate::new(1970, January, 1));
+
+    let calendar = HistoricalCalendar::GREGORIAN_REFORM;
+    assert_eq!(Calendar::month_length(&calendar, 1582, October), 21);
+    assert_eq!(calendar.year_kind(1500), YearKind::Leap);
+    assert_eq!(calendar.year_kind(1700), YearKind::Common);
+    let d = Calendar::date(&calendar, 1582, October, 4).unwrap();
+    assert_eq!(calendar.convert(&d, &Julian), JulianDate::new(1582, October, 4));
+    assert_eq!(calendar.convert(&d, &Gregorian), NaiveDate::new(1582, October, 14));
+    assert_eq!(Gregorian.convert(&NaiveDate::new(1752, September, 13),
+                                 &HistoricalCalendar::GREAT_BRITAIN),
+               HistoricalDate::Julian(JulianDate::new(1752, September, 2)));
+}
+
 // At some point I was skeptical of `#[derive(PartialOrd, Ord)]` on enums,
 // but the bug turned out to be somewhere else.
 #[test]
+#[allow(clippy::nonminimal_bool)]
 fn month_ord() {
     assert!( (January == January));
     assert!(!(January <  January));
@@ -274,6 +1018,514 @@ fn central_europe_dst() {
     test_transitions!(2018, March 25, October 28);
     test_transitions!(2019, March 31, October 27);
     test_transitions!(2020, March 29, October 25);
+
+    let utc = |y, m, d, h| Utc.to_unambiguous_timestamp(&NaiveDateTime::new(y, m, d, h, 0, 0));
+    // 2am local time is repeated in October, and skipped in March.
+    assert_eq!(DateTime::new(CentralEurope, 2016, October, 30, 2, 0, 0).to_timestamp(),
+               Err(LocalTimeConversionError::Ambiguous {
+                   earlier: utc(2016, October, 30, 0),
+                   later: utc(2016, October, 30, 1),
+               }));
+    assert_eq!(DateTime::new(CentralEurope, 2016, March, 27, 2, 0, 0).to_timestamp(),
+               Err(LocalTimeConversionError::Nonexistent {
+                   before: utc(2016, March, 27, 0),
+                   after: utc(2016, March, 27, 1),
+                   transition: utc(2016, March, 27, 1),
+               }));
+
+    // Until 1995, summer time ended in September.
+    assert!(!CentralEurope.is_in_dst(utc(1981, March, 29, 0)));
+    assert!(CentralEurope.is_in_dst(utc(1981, March, 29, 1)));
+    assert!(CentralEurope.is_in_dst(utc(1990, September, 30, 0)));
+    assert!(!CentralEurope.is_in_dst(utc(1990, September, 30, 1)));
+    assert!(!CentralEurope.is_in_dst(utc(1995, October, 1, 12)));
+    assert!(CentralEurope.is_in_dst(utc(1996, October, 27, 0)));
+    assert!(!CentralEurope.is_in_dst(utc(1996, October, 27, 1)));
+    // No summer time before 1981
+    assert!(!CentralEurope.is_in_dst(utc(1975, July, 1, 12)));
+}
+
+#[test]
+fn dst_rule() {
+    let utc = |y, m, d, h, min| Utc.to_unambiguous_timestamp(&NaiveDateTime::new(y, m, d, h, min, 0));
+    for &(rule, posix) in &[
+        (DstRule::NORTH_AMERICA_EASTERN, "EST5EDT,M3.2.0,M11.1.0"),
+        (DstRule::NORTH_AMERICA_PACIFIC, "PST8PDT,M3.2.0,M11.1.0"),
+        (DstRule::UK_IRELAND, "GMT0BST,M3.5.0/1,M10.5.0"),
+        (DstRule::CENTRAL_EUROPE, "CET-1CEST,M3.5.0,M10.5.0/3"),
+        (DstRule::EASTERN_EUROPE, "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+        (DstRule::AUSTRALIA_EASTERN, "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+        (DstRule::NEW_ZEALAND, "NZST-12NZDT,M9.5.0,M4.1.0/3"),
+        (DstRule::CHILE, "<-04>4<-03>,M9.1.6/24,M4.1.6/24"),
+    ] {
+        let posix = posix.parse::<PosixTz>().unwrap();
+        assert_eq!(rule.offset_outside_dst(), posix.offset_outside_dst());
+        assert_eq!(rule.offset_during_dst(), posix.offset_during_dst());
+        for hours in 0..5 * 366 * 24 {
+            let t = UnixTimestamp(utc(2020, January, 1, 0, 0).0 + hours * 3600);
+            assert_eq!(rule.is_in_dst(t), posix.is_in_dst(t));
+        }
+    }
+
+    // Southern hemisphere, spanning New Year
+    let sydney = DstRule::AUSTRALIA_EASTERN;
+    assert!(sydney.is_in_dst(utc(2016, January, 1, 0, 0)));
+    assert!(sydney.is_in_dst(utc(2016, April, 2, 15, 59)));
+    assert!(!sydney.is_in_dst(utc(2016, April, 2, 16, 0)));
+    assert!(!sydney.is_in_dst(utc(2016, October, 1, 15, 59)));
+    assert!(sydney.is_in_dst(utc(2016, October, 1, 16, 0)));
+    assert!(DateTime::new(sydney, 2016, October, 2, 2, 30, 0).to_timestamp().is_err());
+
+    assert_eq!(DstRule::WESTERN_EUROPE, DstRule::UK_IRELAND);
+    assert_eq!(TransitionDay::nth(1, Sunday), Ok(TransitionDay::Nth(1, Sunday)));
+    assert_eq!(TransitionDay::nth(4, Friday), Ok(TransitionDay::Nth(4, Friday)));
+    assert_eq!(TransitionDay::nth(5, Sunday), Ok(TransitionDay::Last(Sunday)));
+    assert_eq!(TransitionDay::nth(0, Sunday), Err(InvalidDateTime::WeekOutOfRange));
+    assert_eq!(TransitionDay::nth(6, Sunday), Err(InvalidDateTime::WeekOutOfRange));
+
+    // First Sunday after the first Saturday
+    let chile = DstRule::CHILE;
+    assert!(!chile.is_in_dst(utc(2024, September, 8, 3, 59)));
+    assert!(chile.is_in_dst(utc(2024, September, 8, 4, 0)));
+    assert!(!chile.is_in_dst(utc(2024, September, 1, 4, 0)));
+    assert!(chile.is_in_dst(utc(2025, April, 6, 2, 59)));
+    assert!(!chile.is_in_dst(utc(2025, April, 6, 3, 0)));
+
+    let custom = DstRule {
+        start: DstTransition {
+            month: April,
+            day: TransitionDay::OnOrAfter(15, Friday),
+            time: -3600,
+            clock: TransitionClock::Standard,
+        },
+        ..DstRule::CENTRAL_EUROPE
+    };
+    assert!(!custom.is_in_dst(utc(2016, April, 14, 21, 59)));
+    assert!(custom.is_in_dst(utc(2016, April, 14, 22, 0)));
+}
+
+#[test]
+fn posix_tz() {
+    fn error(position: usize, kind: ParseErrorKind) -> Result<PosixTz, ParseError> {
+        Err(ParseError { position, kind })
+    }
+    let tz = |s: &str| s.parse::<PosixTz>().unwrap();
+    let utc = |y, m, d, h, min| Utc.to_unambiguous_timestamp(&NaiveDateTime::new(y, m, d, h, min, 0));
+
+    let central_europe = tz("CET-1CEST,M3.5.0,M10.5.0/3");
+    let nuuk = tz("<-02>2<-01>,M3.5.0/-1,M10.5.0/0");
+    assert_eq!(central_europe.offset_outside_dst(), CentralEurope.offset_outside_dst());
+    assert_eq!(central_europe.offset_during_dst(), CentralEurope.offset_during_dst());
+    assert_eq!(nuuk.offset_during_dst(), FixedOffsetFromUtc::from_hours_and_minutes(-1, 0));
+    for hours in 0..20 * 366 * 24 {
+        let t = UnixTimestamp(utc(2010, January, 1, 0, 0).0 + hours * 3600);
+        assert_eq!(central_europe.is_in_dst(t), CentralEurope.is_in_dst(t));
+        assert_eq!(nuuk.is_in_dst(t), CentralEurope.is_in_dst(t));
+    }
+
+    let new_york = tz("EST5EDT,M3.2.0,M11.1.0");
+    assert_eq!(tz("EST5EDT"), new_york);
+    assert!(!new_york.is_in_dst(utc(2016, March, 13, 6, 59)));
+    assert!(new_york.is_in_dst(utc(2016, March, 13, 7, 0)));
+    assert!(new_york.is_in_dst(utc(2016, November, 6, 5, 59)));
+    assert!(!new_york.is_in_dst(utc(2016, November, 6, 6, 0)));
+    assert!(DateTime::new(new_york, 2016, March, 13, 2, 30, 0).to_timestamp().is_err());
+
+    // Thursday of the fourth week at 26:00 is Friday at 2 am.
+    let israel = tz("IST-2IDT,M3.4.4/26,M10.5.0");
+    assert!(!israel.is_in_dst(utc(2016, March, 24, 23, 59)));
+    assert!(israel.is_in_dst(utc(2016, March, 25, 0, 0)));
+
+    let julian = tz("AAA0BBB,J60/0,J61/0");
+    let zero_based = tz("AAA0BBB,59/0,60/0");
+    assert!(julian.is_in_dst(utc(2016, March, 1, 0, 0)));
+    assert!(!julian.is_in_dst(utc(2016, March, 1, 23, 0)));
+    assert!(!zero_based.is_in_dst(utc(2016, March, 1, 0, 0)));
+    assert!(zero_based.is_in_dst(utc(2016, February, 29, 0, 0)));
+    assert!(zero_based.is_in_dst(utc(2015, March, 1, 0, 0)));
+
+    let tokyo = tz("JST-9");
+    assert!(!tokyo.is_in_dst(utc(2016, July, 16, 0, 0)));
+    assert_eq!(tokyo.offset_during_dst(), FixedOffsetFromUtc::from_hours_and_minutes(9, 0));
+
+    assert_eq!("EST".parse::<PosixTz>(), error(3, ParseErrorKind::UnexpectedEnd));
+    assert_eq!("E5".parse::<PosixTz>(), error(1, ParseErrorKind::UnexpectedCharacter));
+    assert_eq!("EST25".parse::<PosixTz>(), error(3, ParseErrorKind::OffsetOutOfRange));
+    assert_eq!("EST5EDT,M13.1.0,M11.1.0".parse::<PosixTz>(),
+               error(9, ParseErrorKind::MonthOutOfRange));
+    assert_eq!("EST5EDT,M3.2.0/168,M11.1.0".parse::<PosixTz>(),
+               error(15, ParseErrorKind::OffsetOutOfRange));
+    assert_eq!("EST5EDT,M3.2.0".parse::<PosixTz>(), error(14, ParseErrorKind::UnexpectedEnd));
+}
+
+#[test]
+fn tzif() {
+    let new_york = Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..]).unwrap();
+    let sydney = Tzif::parse(&include_bytes!("../test_data/Australia_Sydney.tzif")[..]).unwrap();
+    let local = |tz, y, m, d, h, min| DateTime::new(tz, y, m, d, h, min, 0);
+    let utc = |y, m, d, h, min| DateTime::new(Utc, y, m, d, h, min, 0);
+
+    // Local mean time before the first transition
+    assert_eq!(utc(1800, January, 1, 0, 0).convert_time_zone(new_york.clone()).unwrap().naive,
+               NaiveDateTime::new(1799, December, 31, 19, 3, 58));
+    // Transition table
+    assert_eq!(local(new_york.clone(), 2016, July, 16, 14, 58).convert_time_zone(Utc),
+               Ok(utc(2016, July, 16, 18, 58)));
+    assert_eq!(local(new_york.clone(), 2016, January, 16, 14, 58).convert_time_zone(Utc),
+               Ok(utc(2016, January, 16, 19, 58)));
+    assert_eq!(local(new_york.clone(), 2016, March, 13, 2, 30).to_timestamp(),
+               Err(LocalTimeConversionError::Nonexistent {
+                   before: UnixTimestamp::from(utc(2016, March, 13, 6, 30)),
+                   after: UnixTimestamp::from(utc(2016, March, 13, 7, 30)),
+                   transition: UnixTimestamp::from(utc(2016, March, 13, 7, 0)),
+               }));
+    assert_eq!(local(new_york.clone(), 2016, November, 6, 1, 30).to_timestamp(),
+               Err(LocalTimeConversionError::Ambiguous {
+                   earlier: UnixTimestamp::from(utc(2016, November, 6, 5, 30)),
+                   later: UnixTimestamp::from(utc(2016, November, 6, 6, 30)),
+               }));
+    assert_eq!(local(new_york.clone(), 2016, November, 6, 2, 30).convert_time_zone(Utc),
+               Ok(utc(2016, November, 6, 7, 30)));
+    // Footer, after the last transition in 2037
+    assert_eq!(utc(2050, March, 13, 6, 59).convert_time_zone(new_york.clone()),
+               Ok(local(new_york.clone(), 2050, March, 13, 1, 59)));
+    assert_eq!(utc(2050, March, 13, 7, 0).convert_time_zone(new_york.clone()),
+               Ok(local(new_york.clone(), 2050, March, 13, 3, 0)));
+    assert!(local(new_york.clone(), 2050, March, 13, 2, 30).to_timestamp().is_err());
+    assert!(local(new_york.clone(), 2050, November, 6, 1, 30).to_timestamp().is_err());
+    assert_eq!(local(new_york.clone(), 2050, November, 6, 0, 30).convert_time_zone(Utc),
+               Ok(utc(2050, November, 6, 4, 30)));
+
+    // Southern hemisphere
+    assert_eq!(utc(2050, January, 15, 12, 0).convert_time_zone(sydney.clone()),
+               Ok(local(sydney.clone(), 2050, January, 15, 23, 0)));
+    assert_eq!(utc(2050, July, 15, 12, 0).convert_time_zone(sydney.clone()),
+               Ok(local(sydney.clone(), 2050, July, 15, 22, 0)));
+    assert!(local(sydney.clone(), 2050, April, 3, 2, 30).to_timestamp().is_err());
+    assert_eq!(local(sydney.clone(), 2050, October, 2, 2, 30).to_timestamp(),
+               Err(LocalTimeConversionError::Nonexistent {
+                   before: UnixTimestamp::from(utc(2050, October, 1, 15, 30)),
+                   after: UnixTimestamp::from(utc(2050, October, 1, 16, 30)),
+                   transition: UnixTimestamp::from(utc(2050, October, 1, 16, 0)),
+               }));
+    assert_eq!(local(sydney.clone(), 2050, April, 3, 3, 30).convert_time_zone(Utc),
+               Ok(utc(2050, April, 2, 17, 30)));
+
+    assert_eq!(Tzif::parse(&b"TZjf"[..]), Err(TzifError::UnexpectedEnd));
+    assert_eq!(Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..100]),
+               Err(TzifError::UnexpectedEnd));
+    let mut data = include_bytes!("../test_data/America_New_York.tzif").to_vec();
+    data[0] = b'X';
+    assert_eq!(Tzif::parse(data), Err(TzifError::NotTzif));
+    let mut data = include_bytes!("../test_data/America_New_York.tzif").to_vec();
+    let footer = data.len() - 8;
+    data[footer] = b'!';
+    assert_eq!(Tzif::parse(data), Err(TzifError::InvalidFooter));
+
+    // Counts in the header that do not fit in memory
+    let mut data = include_bytes!("../test_data/America_New_York.tzif")[..44].to_vec();
+    for byte in &mut data[32..44] {
+        *byte = 0xFF
+    }
+    assert!(matches!(Tzif::parse(data),
+                     Err(TzifError::UnexpectedEnd) | Err(TzifError::InvalidData)));
+    assert_eq!(::tzif::position_after(44, usize::MAX / 8 + 1, 8), Err(TzifError::InvalidData));
+    assert_eq!(::tzif::position_after(usize::MAX - 1, 2, 1), Err(TzifError::InvalidData));
+    assert_eq!(::tzif::position_after(44, 3, 8), Ok(68));
+}
+
+#[test]
+fn tzif_footer() {
+    let data = &include_bytes!("../test_data/America_New_York.tzif")[..];
+    let t = |y, m, d| UnixTimestamp::from(DateTime::new(Utc, y, m, d, 12, 0, 0));
+    let hours = |h| FixedOffsetFromUtc::from_hours_and_minutes(h, 0);
+
+    // After the last transition in 2037, local time follows the POSIX `TZ` string in the footer.
+    let new_york = Tzif::parse(data).unwrap();
+    let footer = "EST5EDT,M3.2.0,M11.1.0".parse::<PosixTz>().unwrap();
+    for days in (0..20 * 366).step_by(3) {
+        let t = UnixTimestamp(t(2040, January, 1).0 + days * 86400);
+        assert_eq!(new_york.offset_at(t), footer.offset_at(t));
+    }
+
+    let footer_start = data[..data.len() - 1].iter().rposition(|&b| b == b'\n').unwrap() + 1;
+    let with_footer = |footer: &[u8]| {
+        let mut data = data[..footer_start].to_vec();
+        data.extend_from_slice(footer);
+        Tzif::parse(data)
+    };
+    let central_europe = with_footer(b"CET-1CEST,M3.5.0,M10.5.0/3\n").unwrap();
+    assert_eq!(central_europe.offset_at(t(2050, July, 1)), hours(2));
+    assert_eq!(central_europe.offset_at(t(2050, January, 1)), hours(1));
+    assert_eq!(central_europe.abbreviation_at(t(2050, July, 1)), Some("CEST"));
+    // Without a footer, the last local time type stays in effect.
+    let no_footer = with_footer(b"\n").unwrap();
+    assert_eq!(no_footer.offset_at(t(2050, July, 1)), hours(-5));
+    assert_eq!(with_footer(b"EST5EDT,M3.2.0\n"), Err(TzifError::InvalidFooter));
+    assert_eq!(with_footer(b"EST5EDT,M3.2.0,M13.1.0\n"), Err(TzifError::InvalidFooter));
+    assert_eq!(with_footer(b"\xFF\n"), Err(TzifError::InvalidFooter));
+    assert_eq!(with_footer(b"EST5EDT,M3.2.0,M11.1.0"), Err(TzifError::UnexpectedEnd));
+}
+
+#[test]
+fn offset_at() {
+    let t = |y, m, d, h| UnixTimestamp::from(DateTime::new(Utc, y, m, d, h, 0, 0));
+    let hours = |h| FixedOffsetFromUtc::from_hours_and_minutes(h, 0);
+    let summer = t(2016, July, 16, 12);
+    let winter = t(2016, January, 16, 12);
+
+    assert_eq!(Utc.offset_at(summer), hours(0));
+    assert_eq!(Utc.abbreviation_at(summer), Some("UTC"));
+    assert!(!Utc.is_dst_at(summer));
+    assert_eq!(hours(9).offset_at(summer), hours(9));
+    assert_eq!(hours(9).abbreviation_at(summer), None);
+
+    assert_eq!(CentralEurope.offset_at(summer), hours(2));
+    assert_eq!(CentralEurope.offset_at(winter), hours(1));
+    assert_eq!(CentralEurope.abbreviation_at(summer), Some("CEST"));
+    assert_eq!(CentralEurope.abbreviation_at(winter), Some("CET"));
+    assert!(CentralEurope.is_dst_at(summer));
+    assert!(!CentralEurope.is_dst_at(winter));
+    assert_eq!(CentralEurope.offset_at(t(2016, March, 27, 0)), hours(1));
+    assert_eq!(CentralEurope.offset_at(t(2016, March, 27, 1)), hours(2));
+
+    assert_eq!(DstRule::AUSTRALIA_EASTERN.offset_at(winter), hours(11));
+    assert!(DstRule::AUSTRALIA_EASTERN.is_dst_at(winter));
+    assert_eq!(DstRule::AUSTRALIA_EASTERN.abbreviation_at(winter), None);
+
+    let tz: PosixTz = "<+0530>-5:30".parse().unwrap();
+    assert_eq!(tz.offset_at(summer), FixedOffsetFromUtc::from_hours_and_minutes(5, 30));
+    assert_eq!(tz.abbreviation_at(summer), Some("+0530"));
+    assert!(!tz.is_dst_at(summer));
+    let tz: PosixTz = "EST5EDT,M3.2.0,M11.1.0".parse().unwrap();
+    assert_eq!(tz.abbreviation_at(summer), Some("EDT"));
+    assert_eq!(tz.abbreviation_at(winter), Some("EST"));
+    let tz: PosixTz = "ABCDEFGHIJKLMNOPQ3".parse().unwrap();
+    assert_eq!(tz.abbreviation_at(summer), None);
+
+    let new_york = Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..]).unwrap();
+    assert_eq!(new_york.offset_at(summer), hours(-4));
+    assert_eq!(new_york.abbreviation_at(summer), Some("EDT"));
+    assert!(new_york.is_dst_at(summer));
+    assert_eq!(new_york.offset_at(winter), hours(-5));
+    assert_eq!(new_york.abbreviation_at(winter), Some("EST"));
+    assert!(!new_york.is_dst_at(winter));
+    // Local mean time
+    let lmt = t(1800, January, 1, 0);
+    assert_eq!(new_york.offset_at(lmt), FixedOffsetFromUtc::from_seconds(-17762));
+    assert_eq!(new_york.abbreviation_at(lmt), Some("LMT"));
+    // Footer
+    assert_eq!(new_york.offset_at(t(2050, July, 1, 0)), hours(-4));
+    assert_eq!(new_york.abbreviation_at(t(2050, July, 1, 0)), Some("EDT"));
+    assert!(new_york.is_dst_at(t(2050, July, 1, 0)));
+    let d = DateTime::new(new_york, 2050, December, 1, 12, 0, 0);
+    assert_eq!(d.format("%H:%M %Z").to_string(), "12:00 EST");
+}
+
+#[test]
+fn transitions() {
+    use std::vec::Vec;
+    let t = |y, m, d, h| UnixTimestamp::from(DateTime::new(Utc, y, m, d, h, 0, 0));
+    let hours = |h| FixedOffsetFromUtc::from_hours_and_minutes(h, 0);
+
+    let after: Vec<_> = CentralEurope.transitions_after(t(2016, January, 1, 0)).take(4).collect();
+    assert_eq!(after, [
+        Transition { at: t(2016, March, 27, 1), offset_before: hours(1), offset_after: hours(2) },
+        Transition { at: t(2016, October, 30, 1), offset_before: hours(2), offset_after: hours(1) },
+        Transition { at: t(2017, March, 26, 1), offset_before: hours(1), offset_after: hours(2) },
+        Transition { at: t(2017, October, 29, 1), offset_before: hours(2), offset_after: hours(1) },
+    ]);
+    let before: Vec<_> = CentralEurope.transitions_before(after[3].at).collect();
+    assert_eq!(before[..3], [after[2], after[1], after[0]]);
+    assert_eq!(before.len(), 2 * (2017 - 1981) + 1);
+    assert_eq!(before.last().unwrap().at, t(1981, March, 29, 1));
+    assert_eq!(before[2 * (2017 - 1996) + 1].at, t(1995, September, 24, 1));
+    assert_eq!(CentralEurope.next_transition(t(1900, January, 1, 0)).unwrap().at,
+               t(1981, March, 29, 1));
+    assert_eq!(CentralEurope.previous_transition(t(1981, March, 29, 1)), None);
+    assert_eq!(CentralEurope.next_transition(t(2016, March, 27, 1)).unwrap().at,
+               t(2016, October, 30, 1));
+    assert_eq!(CentralEurope.previous_transition(t(2016, March, 27, 1)).unwrap().at,
+               t(2015, October, 25, 1));
+
+    assert_eq!(Utc.next_transition(t(2016, January, 1, 0)), None);
+    assert_eq!(hours(9).previous_transition(t(2016, January, 1, 0)), None);
+    let tz: PosixTz = "JST-9".parse().unwrap();
+    assert_eq!(tz.next_transition(t(2016, January, 1, 0)), None);
+
+    // Southern hemisphere
+    let sydney = DstRule::AUSTRALIA_EASTERN.next_transition(t(2016, January, 1, 0)).unwrap();
+    assert_eq!(sydney, Transition {
+        at: t(2016, April, 2, 16),
+        offset_before: hours(11),
+        offset_after: hours(10),
+    });
+    let tz: PosixTz = "AEST-10AEDT,M10.1.0,M4.1.0/3".parse().unwrap();
+    assert_eq!(tz.next_transition(t(2016, January, 1, 0)), Some(sydney));
+
+    // The transition table, then the footer after 2037
+    let new_york = Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..]).unwrap();
+    let sydney = Tzif::parse(&include_bytes!("../test_data/Australia_Sydney.tzif")[..]).unwrap();
+    let (start, end) = (t(2009, January, 1, 0), t(2060, January, 1, 0));
+    let rules = |tz: &DstRule| -> Vec<_> {
+        tz.transitions_after(start).take_while(|transition| transition.at < end).collect()
+    };
+    let tzif = |tz: &Tzif<&[u8]>| -> Vec<_> {
+        tz.transitions_after(start).take_while(|transition| transition.at < end).collect()
+    };
+    assert_eq!(tzif(&new_york), rules(&DstRule::NORTH_AMERICA_EASTERN));
+    assert_eq!(tzif(&sydney), rules(&DstRule::AUSTRALIA_EASTERN));
+    assert_eq!(tzif(&new_york).len(), 2 * (2060 - 2009));
+    let mut backward: Vec<_> = new_york.transitions_before(end)
+                                       .take_while(|transition| transition.at >= start)
+                                       .collect();
+    backward.reverse();
+    assert_eq!(backward, tzif(&new_york));
+
+    // Local mean time before the first transition
+    let first = new_york.transitions_before(t(1900, January, 1, 0)).last().unwrap();
+    assert_eq!(first, Transition {
+        at: t(1883, November, 18, 17),
+        offset_before: FixedOffsetFromUtc::from_seconds(-17762),
+        offset_after: hours(-5),
+    });
+    assert_eq!(new_york.next_transition(t(1800, January, 1, 0)), Some(first));
+}
+
+#[test]
+fn disambiguation() {
+    use Disambiguation::*;
+    let new_york = Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..]).unwrap();
+    let local = |d, h, min| DateTime::new(new_york.clone(), 2016, November, d, h, min, 0);
+    let utc = |d, h, min| Ok(UnixTimestamp::from(DateTime::new(Utc, 2016, November, d, h, min, 0)));
+
+    // Repeated
+    assert_eq!(local(6, 1, 30).to_timestamp_with(Earlier), utc(6, 5, 30));
+    assert_eq!(local(6, 1, 30).to_timestamp_with(Later), utc(6, 6, 30));
+    assert_eq!(local(6, 1, 30).to_timestamp_with(Compatible), utc(6, 5, 30));
+    assert!(local(6, 1, 30).to_timestamp_with(Reject).is_err());
+
+    // Unambiguous
+    for &disambiguation in &[Earlier, Later, Compatible, Reject] {
+        assert_eq!(local(6, 2, 30).to_timestamp_with(disambiguation), utc(6, 7, 30));
+    }
+
+    // Skipped
+    let gap = NaiveDateTime::new(2016, March, 13, 2, 30, 0);
+    let utc = |h| Ok(Utc.to_unambiguous_timestamp(&NaiveDateTime::new(2016, March, 13, h, 30, 0)));
+    assert_eq!(new_york.to_timestamp_with(&gap, Earlier), utc(6));
+    assert_eq!(new_york.to_timestamp_with(&gap, Later), utc(7));
+    assert_eq!(new_york.to_timestamp_with(&gap, Compatible), utc(7));
+    assert!(new_york.to_timestamp_with(&gap, Reject).is_err());
+    assert_eq!(CentralEurope.to_timestamp_with(&NaiveDateTime::new(2016, March, 27, 2, 30, 0),
+                                               Compatible),
+               Ok(Utc.to_unambiguous_timestamp(&NaiveDateTime::new(2016, March, 27, 1, 30, 0))));
+
+    let d = DateTime { naive: NaiveDateTime { nanosecond: 5, ..gap }, time_zone: new_york };
+    let expected = NaiveDateTime { nanosecond: 5, ..NaiveDateTime::new(2016, March, 13, 7, 30, 0) };
+    assert_eq!(d.convert_time_zone_with(Utc, Compatible).map(|d| d.naive), Ok(expected));
+}
+
+#[cfg(feature = "system_time")]
+#[test]
+fn zoneinfo() {
+    use std::ffi::OsStr;
+    use std::path::Path;
+    use zoneinfo::{zoneinfo_in, local_from};
+
+    let directory = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/zoneinfo"));
+    let t = UnixTimestamp(1468702726);
+    let hour = |tz: Tzif<_>| DateTime::from_timestamp(t, tz).hour();
+    assert_eq!(hour(zoneinfo_in(directory, "America/New_York").unwrap()), 16);
+    assert_eq!(hour(zoneinfo_in(directory, "Australia/Sydney").unwrap()), 6);
+    for name in &["", "/etc/passwd", "../../etc/passwd", "America/../../etc/passwd",
+                  "America//New_York", "America/New_York\0", "./UTC"] {
+        assert_eq!(Tzif::from_zoneinfo(name), Err(ZoneinfoError::InvalidName));
+        assert_eq!(zoneinfo_in(directory, name), Err(ZoneinfoError::InvalidName));
+    }
+    assert_eq!(zoneinfo_in(directory, "Nowhere/Atlantis"),
+               Err(ZoneinfoError::Io(std::io::ErrorKind::NotFound)));
+
+    let local = |tz: &str| local_from(Some(OsStr::new(tz)), directory);
+    assert_eq!(hour(local("Australia/Sydney").unwrap()), 6);
+    assert_eq!(hour(local(":America/New_York").unwrap()), 16);
+    assert_eq!(hour(local("EST5EDT,M3.2.0,M11.1.0").unwrap()), 16);
+    assert_eq!(DateTime::from_timestamp(t, local("<+0330>-3:30").unwrap()).minute(), 28);
+    assert_eq!(hour(local("").unwrap()), 20);
+    assert_eq!(local(":Nowhere/Atlantis"), Err(ZoneinfoError::Io(std::io::ErrorKind::NotFound)));
+    assert_eq!(local("Nowhere/Atlantis"), Err(ZoneinfoError::InvalidName));
+    let absolute = directory.join("Australia/Sydney");
+    assert_eq!(hour(local(absolute.to_str().unwrap()).unwrap()), 6);
+}
+
+#[cfg(feature = "tzdb")]
+#[test]
+fn tzdb() {
+    let utc = |y, m, d, h, min| Utc.to_unambiguous_timestamp(&NaiveDateTime::new(y, m, d, h, min, 0));
+    let new_york = Tzif::parse(&include_bytes!("../test_data/America_New_York.tzif")[..]).unwrap();
+    let sydney = Tzif::parse(&include_bytes!("../test_data/Australia_Sydney.tzif")[..]).unwrap();
+    for year in 1900..2100 {
+        for &month in &[January, March, April, June, October, November] {
+            for &day in &[1, 2, 6, 7, 13, 14] {
+                // Around transitions, which are at 2 or 3 am
+                for hour in 0..5 {
+                    let d = NaiveDateTime::new(year, month, day, hour, 30, 0);
+                    assert_eq!(tzdb::America::New_York.to_timestamp(&d), new_york.to_timestamp(&d));
+                    assert_eq!(tzdb::Australia::Sydney.to_timestamp(&d), sydney.to_timestamp(&d));
+                }
+            }
+        }
+    }
+
+    for hours in 0..20 * 366 * 24 {
+        let t = UnixTimestamp(utc(1981, January, 1, 0, 0).0 + hours * 3600);
+        assert_eq!(CentralEurope.from_timestamp(t), tzdb::Europe::Berlin.from_timestamp(t));
+    }
+
+    let t = UnixTimestamp(1468702726);
+    let hour = |tz| DateTime::from_timestamp(t, tz).hour();
+    assert_eq!(hour(tzdb::by_name("America/New_York").unwrap()), 16);
+    assert_eq!(hour(tzdb::by_name("US/Eastern").unwrap()), 16);
+    assert_eq!(DateTime::from_timestamp(t, tzdb::US::Eastern).hour(), 16);
+    assert_eq!(DateTime::from_timestamp(t, tzdb::Asia::Tokyo).hour(), 5);
+    assert_eq!(DateTime::from_timestamp(t, tzdb::Etc::GMT_plus_5).hour(), 15);
+    // No DST in Haiti in 2016
+    assert_eq!(DateTime::from_timestamp(t, tzdb::America::Port_au_Prince).hour(), 15);
+    assert_eq!(DateTime::from_timestamp(t, tzdb::UTC).hour(), 20);
+    assert_eq!(tzdb::Europe::Paris.abbreviation_at(t), Some("CEST"));
+    assert_eq!(tzdb::Europe::Paris.abbreviation_at(utc(2100, January, 1, 0, 0)), Some("CET"));
+    assert!(tzdb::Europe::Paris.is_dst_at(t));
+    assert_eq!(tzdb::Etc::GMT_plus_5.abbreviation_at(t), Some("-05"));
+    assert_eq!(tzdb::Asia::Kolkata.offset_at(t), FixedOffsetFromUtc::from_hours_and_minutes(5, 30));
+    let start = utc(1981, January, 1, 0, 0);
+    let berlin: std::vec::Vec<_> = tzdb::Europe::Berlin.transitions_after(start).take(80).collect();
+    assert!(CentralEurope.transitions_after(start).take(80).eq(berlin));
+    assert!(tzdb::America::New_York.transitions_before(utc(2100, January, 1, 0, 0)).take(400)
+                                   .eq(new_york.transitions_before(utc(2100, January, 1, 0, 0))
+                                               .take(400)));
+    assert_eq!(tzdb::by_name("Nowhere/Atlantis"), None);
+    assert_eq!(tzdb::by_name("america/new_york"), None);
+
+    // Footers are parsed when compiling, and apply after the last explicit transition.
+    for &(name, footer) in &[
+        ("America/New_York", "EST5EDT,M3.2.0,M11.1.0"),
+        ("Europe/Paris", "CET-1CEST,M3.5.0,M10.5.0/3"),
+        ("Australia/Lord_Howe", "<+1030>-10:30<+11>-11,M10.1.0,M4.1.0"),
+        ("America/Nuuk", "<-02>2<-01>,M3.5.0/-1,M10.5.0/0"),
+        ("America/Santiago", "<-04>4<-03>,M9.1.6/24,M4.1.6/24"),
+        ("Asia/Jerusalem", "IST-2IDT,M3.4.4/26,M10.5.0"),
+        ("Pacific/Chatham", "<+1245>-12:45<+1345>,M9.5.0/2:45,M4.1.0/3:45"),
+        ("Asia/Tokyo", "JST-9"),
+    ] {
+        let tz = tzdb::by_name(name).unwrap();
+        let footer = footer.parse::<PosixTz>().unwrap();
+        for days in (0..10 * 366).step_by(5) {
+            let t = UnixTimestamp(utc(2040, January, 1, 0, 0).0 + days * 86400);
+            assert_eq!(tz.offset_at(t), footer.offset_at(t), "{}", name);
+            assert_eq!(tz.abbreviation_at(t), footer.abbreviation_at(t), "{}", name);
+            assert_eq!(tz.next_transition(t), footer.next_transition(t), "{}", name);
+        }
+    }
 }
 
 #[test]
@@ -294,6 +1546,7 @@ fn naive_date_time_ord() {
         hour: 12,
         minute: 30,
         second: 45,
+        nanosecond: 0,
     };
 
     let ndt2 = NaiveDateTime {
@@ -311,6 +1564,10 @@ fn naive_date_time_ord() {
 
     assert!(ndt1 < ndt3);
 
+    let ndt9 = NaiveDateTime {
+        nanosecond: 1,
+        .. ndt1
+    };
     let ndt10 = NaiveDateTime {
         second: 46,
         .. ndt1
@@ -336,7 +1593,7 @@ fn naive_date_time_ord() {
         .. ndt1
     };
 
-    let order = [ndt1, ndt10, ndt11, ndt12, ndt13, ndt14, ndt15];
+    let order = [ndt1, ndt9, ndt10, ndt11, ndt12, ndt13, ndt14, ndt15];
 
     for i in 0..order.len() {
         for j in i..order.len() {
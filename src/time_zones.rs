@@ -1,35 +1,179 @@
-use core::fmt;
-use super::{NaiveDateTime, DateTime, UnixTimestamp, Month, DayOfTheWeek};
+use core::{cmp, str};
+use core::convert::TryFrom;
+use super::{NaiveDateTime, NaiveDate, UnixTimestamp, UnixTimestampNanos, Month, DstRule,
+            DstTransition};
+use dst_rule::start_and_end;
 use num::{div_floor, positive_rem};
 
+#[allow(clippy::wrong_self_convention)]
 pub trait TimeZone {
     fn from_timestamp(&self, t: UnixTimestamp) -> NaiveDateTime;
+
+    /// The `nanosecond` field of `d` is ignored.
     fn to_timestamp(&self, d: &NaiveDateTime) -> Result<UnixTimestamp, LocalTimeConversionError>;
+
+    /// UTC offsets are a whole number of seconds,
+    /// so the fraction of a second is the same in every time zone.
+    fn from_timestamp_nanos(&self, t: UnixTimestampNanos) -> NaiveDateTime {
+        self.from_timestamp(UnixTimestamp(t.seconds)).with_nanosecond(t.nanoseconds)
+    }
+
+    fn to_timestamp_nanos(&self, d: &NaiveDateTime)
+                          -> Result<UnixTimestampNanos, LocalTimeConversionError> {
+        self.to_timestamp(d).map(|t| UnixTimestampNanos {
+            seconds: t.0,
+            nanoseconds: d.nanosecond,
+        })
+    }
+
+    /// Like `to_timestamp`, but ambiguous or nonexistent local times
+    /// are resolved as specified by `disambiguation`.
+    fn to_timestamp_with(&self, d: &NaiveDateTime, disambiguation: Disambiguation)
+                         -> Result<UnixTimestamp, LocalTimeConversionError> {
+        use self::Disambiguation::*;
+        use self::LocalTimeConversionError::*;
+        match (self.to_timestamp(d), disambiguation) {
+            (Err(Ambiguous { earlier, .. }), Earlier) |
+            (Err(Ambiguous { earlier, .. }), Compatible) => Ok(earlier),
+            (Err(Ambiguous { later, .. }), Later) => Ok(later),
+            (Err(Nonexistent { before, .. }), Earlier) => Ok(before),
+            (Err(Nonexistent { after, .. }), Later) |
+            (Err(Nonexistent { after, .. }), Compatible) => Ok(after),
+            (result, _) => result,
+        }
+    }
+
+    /// The offset from UTC in effect at `t`.
+    fn offset_at(&self, t: UnixTimestamp) -> FixedOffsetFromUtc {
+        let local = Utc.to_unambiguous_timestamp(&self.from_timestamp(t));
+        FixedOffsetFromUtc::from_seconds((local.0 - t.0) as i32)
+    }
+
+    /// The abbreviation for local time at `t`, such as `CEST`, if known.
+    fn abbreviation_at(&self, _t: UnixTimestamp) -> Option<&str> {
+        None
+    }
+
+    /// Whether daylight saving time is in effect at `t`.
+    fn is_dst_at(&self, _t: UnixTimestamp) -> bool {
+        false
+    }
+
+    /// The first change of offset strictly after `t`, if any.
+    fn next_transition(&self, _t: UnixTimestamp) -> Option<Transition> {
+        None
+    }
+
+    /// The last change of offset strictly before `t`, if any.
+    fn previous_transition(&self, _t: UnixTimestamp) -> Option<Transition> {
+        None
+    }
+
+    /// Changes of offset after `t`, in chronological order.
+    ///
+    /// ```rust
+    /// use gregor::{TimeZone, CentralEurope, DateTime, Utc, UnixTimestamp, Month};
+    /// let start = UnixTimestamp::from(DateTime::new(Utc, 2016, Month::January, 1, 0, 0, 0));
+    /// let end = UnixTimestamp::from(DateTime::new(Utc, 2018, Month::January, 1, 0, 0, 0));
+    /// let days: Vec<_> = CentralEurope.transitions_after(start)
+    ///     .take_while(|transition| transition.at < end)
+    ///     .map(|transition| DateTime::from_timestamp(transition.at, Utc).naive.date().day)
+    ///     .collect();
+    /// assert_eq!(days, [27, 30, 26, 29]);
+    /// ```
+    fn transitions_after(&self, t: UnixTimestamp) -> Transitions<'_, Self> where Self: Sized {
+        Transitions { time_zone: self, t, forward: true }
+    }
+
+    /// Changes of offset before `t`, in reverse chronological order.
+    fn transitions_before(&self, t: UnixTimestamp) -> Transitions<'_, Self> where Self: Sized {
+        Transitions { time_zone: self, t, forward: false }
+    }
 }
 
-/// When a time zone makes clock jump forward or back at any instant in time
-/// (for example twice a year with daylight-saving time, a.k.a. summer-time period)
-/// This error is returned when either:
-///
-/// * Clocks went back and this local time occurred at multiple instants in time,
-///   making its interpretation or conversion ambiguous.
-///
-/// * Clocks jumped forward and this local time did not occur.
-///   It does not represent any real instant in time.
-///   It could be argued that a range of local times all represent the same instant,
-///   but this library does not implement the conversion that way.
-#[derive(Eq, PartialEq)]
-pub struct LocalTimeConversionError {
-    /// Make the type opaque to allow for future extensions
-    _private: (),
+/// A change of the offset from UTC in a time zone.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Transition {
+    /// The first instant with the new offset.
+    pub at: UnixTimestamp,
+    pub offset_before: FixedOffsetFromUtc,
+    pub offset_after: FixedOffsetFromUtc,
 }
 
-impl fmt::Debug for LocalTimeConversionError {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "LocalTimeConversionError")
+/// The transition of `time_zone` at `at`.
+pub fn transition_at<Tz: TimeZone + ?Sized>(time_zone: &Tz, at: UnixTimestamp) -> Transition {
+    Transition {
+        at,
+        offset_before: time_zone.offset_at(UnixTimestamp(at.0 - 1)),
+        offset_after: time_zone.offset_at(at),
     }
 }
 
+/// The result of `TimeZone::transitions_after` or `TimeZone::transitions_before`.
+#[derive(Debug, Clone)]
+pub struct Transitions<'a, Tz: 'a> {
+    time_zone: &'a Tz,
+    t: UnixTimestamp,
+    forward: bool,
+}
+
+impl<'a, Tz: TimeZone> Iterator for Transitions<'a, Tz> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Transition> {
+        let transition = if self.forward {
+            self.time_zone.next_transition(self.t)
+        } else {
+            self.time_zone.previous_transition(self.t)
+        }?;
+        self.t = transition.at;
+        Some(transition)
+    }
+}
+
+/// How to convert a local time that occurred twice or not at all to an instant.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Disambiguation {
+    /// The earlier of two instants,
+    /// or for a skipped local time the local time moved back by the length of the gap.
+    Earlier,
+
+    /// The later of two instants,
+    /// or for a skipped local time the local time moved forward by the length of the gap.
+    Later,
+
+    /// Like `Earlier` for repeated local times and `Later` for skipped local times,
+    /// as in RFC 5545 (iCalendar) and ECMAScript Temporal.
+    Compatible,
+
+    /// Return `Err(LocalTimeConversionError)`, like `TimeZone::to_timestamp`.
+    Reject,
+}
+
+/// When a time zone makes clock jump forward or back at any instant in time
+/// (for example twice a year with daylight-saving time, a.k.a. summer-time period)
+/// converting some local times to an instant fails.
+///
+/// More kinds of errors or more details may be added in future versions.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[non_exhaustive]
+pub enum LocalTimeConversionError {
+    /// Clocks went back and this local time occurred at multiple instants in time,
+    /// making its interpretation or conversion ambiguous.
+    /// These are the first and last of them.
+    Ambiguous { earlier: UnixTimestamp, later: UnixTimestamp },
+
+    /// Clocks jumped forward and this local time did not occur.
+    /// It does not represent any real instant in time.
+    ///
+    /// Clocks jumped forward at `transition`, which is between `before` and `after`:
+    /// they are the instants at which clocks showed this local time
+    /// moved back and forward by the length of the gap.
+    /// For example in New York, 2:30 am on the day clocks jumped from 2 am to 3 am
+    /// gives 1:30 am EST and 3:30 am EDT, and the transition is at 2 am EST (7 am UTC).
+    Nonexistent { before: UnixTimestamp, after: UnixTimestamp, transition: UnixTimestamp },
+}
+
 /// Implemented for time zones where `LocalTimeConversionError` never occurs,
 /// namely for `Utc` and `FixedOffsetFromUtc`.
 ///
@@ -39,6 +183,10 @@ pub trait UnambiguousTimeZone: TimeZone {
     fn to_unambiguous_timestamp(&self, d: &NaiveDateTime) -> UnixTimestamp {
         self.to_timestamp(d).unwrap()
     }
+
+    fn to_unambiguous_timestamp_nanos(&self, d: &NaiveDateTime) -> UnixTimestampNanos {
+        self.to_timestamp_nanos(d).unwrap()
+    }
 }
 
 /// The *Coordinated Universal Time* time time zone.
@@ -48,21 +196,27 @@ pub struct Utc;
 impl UnambiguousTimeZone for Utc {}
 
 impl TimeZone for Utc {
+    /// Panics if the day number does not fit in `i32`, for years beyond about ±5.8 million.
     fn from_timestamp(&self, u: UnixTimestamp) -> NaiveDateTime {
-        let days_since_unix = div_floor(u.0, SECONDS_PER_DAY) as i32;
-        let days = days_since_unix + days_since_d0(1970);
-        let year = div_floor(days * 400, DAYS_PER_400YEARS) as i32;
-        let day_of_the_year = days - days_since_d0(year);
-        let (month, day) = Month::from_day_of_the_year(day_of_the_year, year.into());
+        let days = i32::try_from(div_floor(u.0, SECONDS_PER_DAY)).expect("timestamp out of range");
+        let date = date_from_days_since_unix(days);
         let hour = positive_rem(div_floor(u.0, SECONDS_PER_HOUR), 24) as u8;
         let minute = positive_rem(div_floor(u.0, SECONDS_PER_MINUTE), 60) as u8;
         let second = positive_rem(u.0, 60) as u8;
-        NaiveDateTime::new(year, month, day, hour, minute, second)
+        NaiveDateTime::new(date.year, date.month, date.day, hour, minute, second)
+    }
+
+    fn offset_at(&self, _t: UnixTimestamp) -> FixedOffsetFromUtc {
+        FixedOffsetFromUtc::from_seconds(0)
+    }
+
+    fn abbreviation_at(&self, _t: UnixTimestamp) -> Option<&str> {
+        Some("UTC")
     }
 
     fn to_timestamp(&self, d: &NaiveDateTime) -> Result<UnixTimestamp, LocalTimeConversionError> {
         Ok(UnixTimestamp(
-            i64::from(days_since_unix(d)) * SECONDS_PER_DAY
+            i64::from(days_since_unix(&d.date())) * SECONDS_PER_DAY
             + i64::from(d.hour) * SECONDS_PER_HOUR
             + i64::from(d.minute) * SECONDS_PER_MINUTE
             + i64::from(d.second)
@@ -84,11 +238,19 @@ pub struct FixedOffsetFromUtc {
 }
 
 impl FixedOffsetFromUtc {
-    pub fn from_hours_and_minutes(hours: i32, minutes: i32) -> Self {
+    pub const fn from_hours_and_minutes(hours: i32, minutes: i32) -> Self {
         FixedOffsetFromUtc {
             seconds_ahead_of_utc: (hours * 60 + minutes) * 60,
         }
     }
+
+    pub const fn from_seconds(seconds_ahead_of_utc: i32) -> Self {
+        FixedOffsetFromUtc { seconds_ahead_of_utc }
+    }
+
+    pub fn seconds_ahead_of_utc(&self) -> i32 {
+        self.seconds_ahead_of_utc
+    }
 }
 
 impl UnambiguousTimeZone for FixedOffsetFromUtc {}
@@ -116,22 +278,73 @@ impl TimeZone for FixedOffsetFromUtc {
         // (with more seconds), so *subtract* the offset to make a Unix timestamp.
         Ok(UnixTimestamp(seconds - i64::from(self.seconds_ahead_of_utc)))
     }
+
+    fn offset_at(&self, _t: UnixTimestamp) -> FixedOffsetFromUtc {
+        *self
+    }
 }
 
 pub trait DaylightSaving {
     fn offset_outside_dst(&self) -> FixedOffsetFromUtc;
     fn offset_during_dst(&self) -> FixedOffsetFromUtc;
     fn is_in_dst(&self, t: UnixTimestamp) -> bool;
+
+    /// Such as `CET`, if known.
+    fn abbreviation_outside_dst(&self) -> Option<&str> {
+        None
+    }
+
+    /// Such as `CEST`, if known.
+    fn abbreviation_during_dst(&self) -> Option<&str> {
+        None
+    }
+
+    /// The first instant strictly after `t` when DST starts or ends, if known.
+    fn next_dst_change(&self, _t: UnixTimestamp) -> Option<UnixTimestamp> {
+        None
+    }
+
+    /// The last instant strictly before `t` when DST starts or ends, if known.
+    fn previous_dst_change(&self, _t: UnixTimestamp) -> Option<UnixTimestamp> {
+        None
+    }
+}
+
+/// The first instant in `low + 1..=high` where `is_after` is true, by bisection,
+/// given that it is false at `low`, true at `high`, and changes only once in between.
+pub fn first_instant_after<F: Fn(i64) -> bool>(mut low: i64, mut high: i64, is_after: F) -> i64 {
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if is_after(middle) {
+            high = middle
+        } else {
+            low = middle
+        }
+    }
+    high
+}
+
+/// For `DaylightSaving` time zones where DST starts and ends once a year,
+/// given when it does in a given year in seconds since 1970-01-01 00:00:00 UTC:
+/// the closest DST change after `t` if `forward`, or before it otherwise.
+pub fn yearly_dst_change<Tz, F>(time_zone: &Tz, t: UnixTimestamp, forward: bool, start_and_end: F)
+                                -> Option<UnixTimestamp>
+    where Tz: DaylightSaving + ?Sized, F: Fn(i32) -> [i64; 2] {
+    // Transition times beyond 24 hours can move a transition into a neighboring year.
+    let year = Utc.from_timestamp(t).year;
+    let is_change = |c: i64| {
+        time_zone.is_in_dst(UnixTimestamp(c - 1)) != time_zone.is_in_dst(UnixTimestamp(c))
+    };
+    let changes = (year.saturating_sub(2)..=year.saturating_add(2))
+        .flat_map(start_and_end)
+        .filter(|&c| if forward { c > t.0 } else { c < t.0 })
+        .filter(|&c| is_change(c));
+    if forward { changes.min() } else { changes.max() }.map(UnixTimestamp)
 }
 
 impl<Tz: DaylightSaving> TimeZone for Tz {
     fn from_timestamp(&self, u: UnixTimestamp) -> NaiveDateTime {
-        let offset = if self.is_in_dst(u) {
-            self.offset_during_dst()
-        } else {
-            self.offset_outside_dst()
-        };
-        offset.from_timestamp(u)
+        self.offset_at(u).from_timestamp(u)
     }
 
     fn to_timestamp(&self, d: &NaiveDateTime) -> Result<UnixTimestamp, LocalTimeConversionError> {
@@ -158,85 +371,207 @@ impl<Tz: DaylightSaving> TimeZone for Tz {
         //   it’s ambiguous.
         // * In March when clocks go "forward", that hour is skipped entirely.
         //   This kind of local time does not exist. This `d` value might come from buggy code.
+        let earlier = cmp::min(assuming_outside, assuming_during);
+        let later = cmp::max(assuming_outside, assuming_during);
         match (self.is_in_dst(assuming_outside), self.is_in_dst(assuming_during)) {
             (true, true) => Ok(assuming_during),
             (false, false) => Ok(assuming_outside),
-            _ => Err(LocalTimeConversionError { _private: () }),
+            (false, true) => Err(LocalTimeConversionError::Ambiguous { earlier, later }),
+            (true, false) => Err(LocalTimeConversionError::Nonexistent {
+                before: earlier,
+                after: later,
+                transition: UnixTimestamp(first_instant_after(earlier.0, later.0, |t| {
+                    self.is_in_dst(UnixTimestamp(t)) == self.is_in_dst(later)
+                })),
+            }),
+        }
+    }
+
+    fn offset_at(&self, t: UnixTimestamp) -> FixedOffsetFromUtc {
+        if self.is_in_dst(t) {
+            self.offset_during_dst()
+        } else {
+            self.offset_outside_dst()
+        }
+    }
+
+    fn abbreviation_at(&self, t: UnixTimestamp) -> Option<&str> {
+        if self.is_in_dst(t) {
+            self.abbreviation_during_dst()
+        } else {
+            self.abbreviation_outside_dst()
+        }
+    }
+
+    fn is_dst_at(&self, t: UnixTimestamp) -> bool {
+        self.is_in_dst(t)
+    }
+
+    fn next_transition(&self, t: UnixTimestamp) -> Option<Transition> {
+        self.next_dst_change(t).map(|at| transition_at(self, at))
+    }
+
+    fn previous_transition(&self, t: UnixTimestamp) -> Option<Transition> {
+        self.previous_dst_change(t).map(|at| transition_at(self, at))
+    }
+}
+
+/// A time zone abbreviation of up to 16 bytes, stored without allocation.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Abbreviation {
+    bytes: [u8; 16],
+    length: u8,
+}
+
+impl Abbreviation {
+    /// Returns `None` if `s` is too long.
+    pub const fn new(s: &str) -> Option<Self> {
+        let mut bytes = [0; 16];
+        if s.len() > bytes.len() {
+            return None
+        }
+        // A loop rather than `copy_from_slice`, so that this works in a `const` context.
+        let mut i = 0;
+        while i < s.len() {
+            bytes[i] = s.as_bytes()[i];
+            i += 1
         }
+        Some(Abbreviation { bytes, length: s.len() as u8 })
+    }
+
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.bytes[..usize::from(self.length)]).unwrap()
     }
 }
 
 /// CET (Central European Time) / CEST (Central European Summer Time)
+///
+/// Summer time follows the European Union directives since 1981,
+/// when they first applied to every member state.
+/// Earlier rules differed between countries, so before 1981 this is always CET.
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
 pub struct CentralEurope;
 
+/// Until 1995, summer time ended on the last Sunday of September.
+const CENTRAL_EUROPE_1981_TO_1995: DstRule = DstRule {
+    end: DstTransition { month: Month::September, ..DstRule::CENTRAL_EUROPE.end },
+    ..DstRule::CENTRAL_EUROPE
+};
+
+/// DST changes before 1981 are ignored by `yearly_dst_change` since `is_in_dst` is false.
+fn central_europe_start_and_end(year: i32) -> [i64; 2] {
+    if year < 1996 {
+        start_and_end(&CENTRAL_EUROPE_1981_TO_1995, year)
+    } else {
+        start_and_end(&DstRule::CENTRAL_EUROPE, year)
+    }
+}
+
 impl DaylightSaving for CentralEurope {
     fn offset_outside_dst(&self) -> FixedOffsetFromUtc {
-        FixedOffsetFromUtc::from_hours_and_minutes(1, 0)
+        DstRule::CENTRAL_EUROPE.standard_offset
     }
 
     fn offset_during_dst(&self) -> FixedOffsetFromUtc {
-        FixedOffsetFromUtc::from_hours_and_minutes(2, 0)
+        DstRule::CENTRAL_EUROPE.dst_offset
     }
 
-    fn is_in_dst(&self, t: UnixTimestamp) -> bool {
-        use Month::*;
+    fn abbreviation_outside_dst(&self) -> Option<&str> {
+        Some("CET")
+    }
 
-        let d = DateTime::from_timestamp(t, Utc);
+    fn abbreviation_during_dst(&self) -> Option<&str> {
+        Some("CEST")
+    }
 
-        // Directive 2000/84/EC of the European Parliament and of the Council
-        // of 19 January 2001 on summer-time arrangements
-        // http://eur-lex.europa.eu/legal-content/EN/ALL/?uri=CELEX:32000L0084
-        //
-        // > Article 1
-        //
-        // > For the purposes of this Directive "summer-time period"
-        // > shall mean the period of the year
-        // > during which clocks are put forward by 60 minutes compared with the rest of the year.
-        // >
-        // > Article 2
-        // >
-        // > From 2002 onwards, the summer-time period shall begin, in every Member State,
-        // > at 1.00 a.m., Greenwich Mean Time, on the last Sunday in March.
-        // >
-        // > Article 3
-        // >
-        // > From 2002 onwards, the summer-time period shall end, in every Member State,
-        // > at 1.00 a.m., Greenwich Mean Time, on the last Sunday in October.
-        if d.month() < March || d.month() > October {
+    fn next_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        // There is no DST change in the first days of 1981.
+        let start_of_1981 = Utc.to_unambiguous_timestamp(&NaiveDateTime::new(1981, Month::January,
+                                                                               1, 0, 0, 0));
+        let t = cmp::max(t, start_of_1981);
+        yearly_dst_change(self, t, true, central_europe_start_and_end)
+    }
+
+    fn previous_dst_change(&self, t: UnixTimestamp) -> Option<UnixTimestamp> {
+        yearly_dst_change(self, t, false, central_europe_start_and_end)
+    }
+
+    fn is_in_dst(&self, t: UnixTimestamp) -> bool {
+        // Summer time is between March and October, so the year in UTC is the local year.
+        let year = Utc.from_timestamp(t).year;
+        if year < 1981 {
             false
-        } else if d.month() > March && d.month() < October {
-            true
-        } else if d.month() == March {
-            !before_last_sunday_1_am(&d)
-        } else if d.month() == October {
-            before_last_sunday_1_am(&d)
+        } else if year < 1996 {
+            // Directive 80/737/EEC and its successors until 94/21/EC:
+            // from the last Sunday in March to the last Sunday in September,
+            // at 1.00 a.m., Greenwich Mean Time.
+            CENTRAL_EUROPE_1981_TO_1995.is_in_dst(t)
         } else {
-            unreachable!()
+            // Directive 2000/84/EC of the European Parliament and of the Council
+            // of 19 January 2001 on summer-time arrangements
+            // http://eur-lex.europa.eu/legal-content/EN/ALL/?uri=CELEX:32000L0084
+            //
+            // > Article 1
+            //
+            // > For the purposes of this Directive "summer-time period"
+            // > shall mean the period of the year
+            // > during which clocks are put forward by 60 minutes
+            // > compared with the rest of the year.
+            // >
+            // > Article 2
+            // >
+            // > From 2002 onwards, the summer-time period shall begin, in every Member State,
+            // > at 1.00 a.m., Greenwich Mean Time, on the last Sunday in March.
+            // >
+            // > Article 3
+            // >
+            // > From 2002 onwards, the summer-time period shall end, in every Member State,
+            // > at 1.00 a.m., Greenwich Mean Time, on the last Sunday in October.
+            //
+            // Directive 94/21/EC set the same end date from 1996.
+            DstRule::CENTRAL_EUROPE.is_in_dst(t)
         }
     }
 }
 
-fn before_last_sunday_1_am(d: &DateTime<Utc>) -> bool {
-    let last_sunday = last_of_the_month(d, DayOfTheWeek::Sunday);
-    d.day() < last_sunday || (
-        d.day() == last_sunday &&
-        (d.hour(), d.minute(), d.second()) < (1, 0, 0)
-    )
+/// Whether a timestamp can be converted to a date-time in any time zone:
+/// its day number fits in `i32` even after adding any `FixedOffsetFromUtc`.
+pub fn is_in_supported_range(t: UnixTimestamp) -> bool {
+    let max_offset = i64::from(i32::MAX);
+    let min = i64::from(i32::MIN) * SECONDS_PER_DAY + max_offset;
+    let max = (i64::from(i32::MAX) + 1) * SECONDS_PER_DAY - 1 - max_offset;
+    min <= t.0 && t.0 <= max
 }
 
-fn last_of_the_month(d: &DateTime<Utc>, requested_dow: DayOfTheWeek) -> u8 {
-    let last_day = d.month().length(d.year().into());
-    let last_dow = NaiveDateTime::new(d.year(), d.month(), last_day, 0, 0, 0).day_of_the_week();
-    let difference = i32::from(last_dow.to_iso_number()) - i32::from(requested_dow.to_iso_number());
-    last_day - (positive_rem(difference, 7) as u8)
+/// Whether every date-time in this year can be converted to a timestamp in any time zone:
+/// about ±5.8 million years, a bit less than all dates with an `i32` day number.
+pub fn is_year_in_supported_range(year: i32) -> bool {
+    let first_day = days_since_d0(year) - days_since_d0(1970);
+    let start = UnixTimestamp(first_day * SECONDS_PER_DAY);
+    let end = UnixTimestamp((first_day + 366) * SECONDS_PER_DAY - 1);
+    is_in_supported_range(start) && is_in_supported_range(end)
 }
 
-pub fn days_since_unix(d: &NaiveDateTime) -> i32 {
-    (d.year - 1970) * DAYS_PER_COMMON_YEAR
-    + leap_days_since_y0(d.year) - leap_days_since_y0(1970)
-    + d.month.days_since_january_1st(d.year.into())
-    + i32::from(d.day - 1)
+pub fn days_since_unix(d: &NaiveDate) -> i32 {
+    let days = days_since_d0(d.year) - days_since_d0(1970)
+             + i64::from(d.month.days_since_january_1st(d.year.into()))
+             + i64::from(d.day) - 1;
+    i32::try_from(days).expect("date out of range for a day number")
+}
+
+pub fn date_from_days_since_unix(days_since_unix: i32) -> NaiveDate {
+    // In 64 bits so that `days * 400` does not overflow. The year fits in `i32`.
+    let days = i64::from(days_since_unix) + days_since_d0(1970);
+    let mut year = div_floor(days * 400, i64::from(DAYS_PER_400YEARS)) as i32;
+    // This estimate can be off by one year around January 1st.
+    if days >= days_since_d0(year + 1) {
+        year += 1
+    } else if days < days_since_d0(year) {
+        year -= 1
+    }
+    let day_of_the_year = (days - days_since_d0(year)) as i32;
+    let (month, day) = Month::from_day_of_the_year(day_of_the_year, year.into());
+    NaiveDate::new(year, month, day)
 }
 
 /// How many leap days occurred between January of year 0 and January of the given year
@@ -253,8 +588,8 @@ pub fn leap_days_since_y0(year: i32) -> i32 {
 }
 
 /// Days between January 1st of year 0 and January 1st of the given year.
-fn days_since_d0(year: i32) -> i32 {
-    year * DAYS_PER_COMMON_YEAR + leap_days_since_y0(year)
+fn days_since_d0(year: i32) -> i64 {
+    i64::from(year) * i64::from(DAYS_PER_COMMON_YEAR) + i64::from(leap_days_since_y0(year))
 }
 
 const SECONDS_PER_MINUTE: i64 = 60;
@@ -0,0 +1,76 @@
+//! The IANA time zone database, compiled into the library with the `tzdb` Cargo feature.
+//!
+//! `build.rs` compiles the vendored `tzdata/tzdata.zi` into TZif data,
+//! so this works without `std` or a file system.
+//! That data is parsed at compile time: conversions only read it.
+//!
+//! Each zone or link is also a unit struct implementing `TimeZone`,
+//! in modules named after the parts of its name.
+//! For example `America/New_York` is `tzdb::America::New_York`.
+//! In names, `-` becomes `_`, and `+` or `-` before a digit becomes `_plus_` or `_minus_`:
+//! `Etc/GMT+5` is `tzdb::Etc::GMT_plus_5`.
+
+use super::{Tzif, PosixTz};
+use tzif::from_compiled_data;
+
+const DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/tzdb.bin"));
+
+macro_rules! zone {
+    ($name: ident, $iana_name: expr, $index: expr) => {
+        #[doc = $iana_name]
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+        pub struct $name;
+
+        impl $crate::TimeZone for $name {
+            fn from_timestamp(&self, t: $crate::UnixTimestamp) -> $crate::NaiveDateTime {
+                $crate::tzdb::ZONES[$index].1.from_timestamp(t)
+            }
+
+            fn to_timestamp(&self, d: &$crate::NaiveDateTime)
+                            -> Result<$crate::UnixTimestamp, $crate::LocalTimeConversionError> {
+                $crate::tzdb::ZONES[$index].1.to_timestamp(d)
+            }
+
+            fn offset_at(&self, t: $crate::UnixTimestamp) -> $crate::FixedOffsetFromUtc {
+                $crate::tzdb::ZONES[$index].1.offset_at(t)
+            }
+
+            fn abbreviation_at(&self, t: $crate::UnixTimestamp) -> Option<&str> {
+                $crate::tzdb::ZONES[$index].1.abbreviation_at(t)
+            }
+
+            fn is_dst_at(&self, t: $crate::UnixTimestamp) -> bool {
+                $crate::tzdb::ZONES[$index].1.is_dst_at(t)
+            }
+
+            fn next_transition(&self, t: $crate::UnixTimestamp) -> Option<$crate::Transition> {
+                $crate::tzdb::ZONES[$index].1.next_transition(t)
+            }
+
+            fn previous_transition(&self, t: $crate::UnixTimestamp)
+                                   -> Option<$crate::Transition> {
+                $crate::tzdb::ZONES[$index].1.previous_transition(t)
+            }
+        }
+    }
+}
+
+// `ZONES`, sorted by name, and modules of unit structs
+include!(concat!(env!("OUT_DIR"), "/tzdb.rs"));
+
+const fn compiled(start: usize, end: usize, footer: Option<PosixTz>) -> Tzif<&'static [u8]> {
+    from_compiled_data(DATA.split_at(end).0.split_at(start).1, footer)
+}
+
+/// Find a zone or link by its name, such as `Europe/Paris`.
+///
+/// ```rust
+/// use gregor::{tzdb, DateTime, UnixTimestamp};
+/// let paris = tzdb::by_name("Europe/Paris").unwrap();
+/// let d = DateTime::from_timestamp(UnixTimestamp(1468702726), paris);
+/// assert_eq!(d.hour(), 22);
+/// ```
+pub fn by_name(name: &str) -> Option<Tzif<&'static [u8]>> {
+    ZONES.binary_search_by(|&(zone_name, _)| zone_name.cmp(name)).ok().map(|i| ZONES[i].1.clone())
+}
@@ -0,0 +1,385 @@
+//! Time zones from TZif data, the binary format of the IANA time zone database
+//! typically found in `/usr/share/zoneinfo`.
+//!
+//! https://tools.ietf.org/html/rfc8536
+
+use core::{cmp, str};
+use super::{NaiveDateTime, UnixTimestamp, TimeZone, UnambiguousTimeZone, DaylightSaving, Utc,
+            FixedOffsetFromUtc, LocalTimeConversionError, PosixTz, Transition};
+use time_zones::{transition_at, first_instant_after};
+
+/// Returned by `Tzif::parse` for invalid or unsupported data.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum TzifError {
+    /// The data does not start with `TZif`.
+    NotTzif,
+
+    /// The data ended before the end of its header, data block, or footer.
+    UnexpectedEnd,
+
+    /// Counts in the header, type indices, or the order of transition times are not valid.
+    InvalidData,
+
+    /// The data contains leap second records.
+    /// Their transition times are not Unix timestamps, so they are not supported.
+    LeapSeconds,
+
+    /// The footer is not a valid POSIX `TZ` string.
+    InvalidFooter,
+}
+
+/// A time zone defined by TZif data, such as a file from `/usr/share/zoneinfo`.
+///
+/// `B` is the storage for the data, for example `&'static [u8]` from `include_bytes!`
+/// which works without `std`.
+/// The data is validated once by `parse`, then read in place for each conversion.
+///
+/// Before the first transition, the first local time type is used.
+/// After the last transition, the POSIX `TZ` string in the footer of version 2 and later data
+/// is used if present.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Tzif<B: AsRef<[u8]>> {
+    data: B,
+    block: Block,
+    footer: Option<PosixTz>,
+}
+
+/// Where to find things in the data block.
+/// Version 1 data is only used when there is no version 2+ data.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Block {
+    /// Size of a transition time: 4 bytes in version 1, 8 in version 2+
+    time_size: usize,
+    transition_count: usize,
+    transition_times: usize,
+    transition_types: usize,
+    local_time_type_count: usize,
+    local_time_types: usize,
+    chars: usize,
+    char_count: usize,
+}
+
+/// What determines local time at a given instant.
+enum LocalTimeType<'a> {
+    /// An index into the local time types of the data block
+    Index(usize),
+    Footer(&'a PosixTz),
+}
+
+const HEADER_SIZE: usize = 44;
+const LOCAL_TIME_TYPE_SIZE: usize = 6;
+
+const fn read_u32(data: &[u8], position: usize) -> u32 {
+    (data[position] as u32) << 24 | (data[position + 1] as u32) << 16 |
+    (data[position + 2] as u32) << 8 | data[position + 3] as u32
+}
+
+fn read_i64(data: &[u8], position: usize) -> i64 {
+    (u64::from(read_u32(data, position)) << 32 | u64::from(read_u32(data, position + 4))) as i64
+}
+
+/// The position after `count` items of `size` bytes starting at `position`.
+///
+/// Counts come from the data, so this is checked: on 32-bit targets it could overflow.
+pub fn position_after(position: usize, count: usize, size: usize) -> Result<usize, TzifError> {
+    count.checked_mul(size)
+         .and_then(|length| position.checked_add(length))
+         .ok_or(TzifError::InvalidData)
+}
+
+/// Read a header and validate its data block, and return where the block ends.
+/// Transitions and local time types are only checked if `validate` is true.
+fn parse_block(data: &[u8], start: usize, time_size: usize, validate: bool)
+               -> Result<(Block, usize), TzifError> {
+    if data.len() < start + HEADER_SIZE {
+        return Err(TzifError::UnexpectedEnd)
+    }
+    if &data[start..start + 4] != b"TZif" {
+        return Err(TzifError::NotTzif)
+    }
+    let count = |index: usize| read_u32(data, start + 20 + 4 * index) as usize;
+    let (ut_indicator_count, standard_indicator_count) = (count(0), count(1));
+    let (leap_count, transition_count) = (count(2), count(3));
+    let (local_time_type_count, char_count) = (count(4), count(5));
+
+    if leap_count != 0 {
+        return Err(TzifError::LeapSeconds)
+    }
+    if local_time_type_count == 0 || char_count == 0 ||
+       (ut_indicator_count != 0 && ut_indicator_count != local_time_type_count) ||
+       (standard_indicator_count != 0 && standard_indicator_count != local_time_type_count) {
+        return Err(TzifError::InvalidData)
+    }
+
+    let transition_times = start + HEADER_SIZE;
+    let transition_types = position_after(transition_times, transition_count, time_size)?;
+    let local_time_types = position_after(transition_types, transition_count, 1)?;
+    let chars = position_after(local_time_types, local_time_type_count, LOCAL_TIME_TYPE_SIZE)?;
+    let end = position_after(chars, char_count, 1)?;
+    let end = position_after(end, standard_indicator_count, 1)?;
+    let end = position_after(end, ut_indicator_count, 1)?;
+    if data.len() < end {
+        return Err(TzifError::UnexpectedEnd)
+    }
+
+    let block = Block {
+        time_size,
+        transition_count,
+        transition_times,
+        transition_types,
+        local_time_type_count,
+        local_time_types,
+        chars,
+        char_count,
+    };
+    if !validate {
+        return Ok((block, end))
+    }
+    for i in 0..transition_count {
+        if i > 0 && block.transition_time(data, i - 1) >= block.transition_time(data, i) {
+            return Err(TzifError::InvalidData)
+        }
+        if usize::from(data[transition_types + i]) >= local_time_type_count {
+            return Err(TzifError::InvalidData)
+        }
+    }
+    for i in 0..local_time_type_count {
+        let position = local_time_types + i * LOCAL_TIME_TYPE_SIZE;
+        let is_dst = data[position + 4];
+        let abbreviation_index = usize::from(data[position + 5]);
+        if read_u32(data, position) as i32 == i32::MIN || is_dst > 1 ||
+           abbreviation_index >= char_count {
+            return Err(TzifError::InvalidData)
+        }
+    }
+    Ok((block, end))
+}
+
+impl Block {
+    fn transition_time(&self, data: &[u8], index: usize) -> i64 {
+        let position = self.transition_times + index * self.time_size;
+        if self.time_size == 4 {
+            i64::from(read_u32(data, position) as i32)
+        } else {
+            read_i64(data, position)
+        }
+    }
+
+    fn offset(&self, data: &[u8], local_time_type: usize) -> FixedOffsetFromUtc {
+        let position = self.local_time_types + local_time_type * LOCAL_TIME_TYPE_SIZE;
+        FixedOffsetFromUtc::from_seconds(read_u32(data, position) as i32)
+    }
+
+    fn is_dst(&self, data: &[u8], local_time_type: usize) -> bool {
+        data[self.local_time_types + local_time_type * LOCAL_TIME_TYPE_SIZE + 4] == 1
+    }
+
+    /// `None` if empty or not UTF-8.
+    fn abbreviation<'a>(&self, data: &'a [u8], local_time_type: usize) -> Option<&'a str> {
+        let index = data[self.local_time_types + local_time_type * LOCAL_TIME_TYPE_SIZE + 5];
+        let chars = &data[self.chars + usize::from(index)..self.chars + self.char_count];
+        let length = chars.iter().position(|&b| b == 0).unwrap_or(chars.len());
+        str::from_utf8(&chars[..length]).ok().filter(|s| !s.is_empty())
+    }
+}
+
+impl<B: AsRef<[u8]>> Tzif<B> {
+    /// Parse and validate TZif data of version 1, 2, 3, or 4.
+    pub fn parse(data: B) -> Result<Self, TzifError> {
+        Tzif::parse_with(data, true)
+    }
+
+    fn parse_with(data: B, validate: bool) -> Result<Self, TzifError> {
+        let (block, footer) = {
+            let bytes = data.as_ref();
+            let (v1_block, v1_end) = parse_block(bytes, 0, 4, validate)?;
+            if bytes[4] == 0 {
+                (v1_block, None)
+            } else {
+                let (block, end) = parse_block(bytes, v1_end, 8, validate)?;
+                if bytes.len() < end + 2 || bytes[end] != b'\n' {
+                    return Err(TzifError::UnexpectedEnd)
+                }
+                let footer_length = bytes[end + 1..].iter().position(|&b| b == b'\n')
+                    .ok_or(TzifError::UnexpectedEnd)?;
+                let footer = &bytes[end + 1..end + 1 + footer_length];
+                let footer = if footer.is_empty() {
+                    None
+                } else {
+                    let footer = ::core::str::from_utf8(footer)
+                        .map_err(|_| TzifError::InvalidFooter)?;
+                    Some(footer.parse::<PosixTz>().map_err(|_| TzifError::InvalidFooter)?)
+                };
+                (block, footer)
+            }
+        };
+        Ok(Tzif { data, block, footer })
+    }
+
+    /// The number of transitions at or before `t`, by binary search.
+    fn transitions_until(&self, t: UnixTimestamp) -> usize {
+        let data = self.data.as_ref();
+        let block = &self.block;
+        let (mut low, mut high) = (0, block.transition_count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if block.transition_time(data, middle) <= t.0 {
+                low = middle + 1
+            } else {
+                high = middle
+            }
+        }
+        low
+    }
+
+    fn table_transition(&self, index: usize) -> Transition {
+        transition_at(self, UnixTimestamp(self.block.transition_time(self.data.as_ref(), index)))
+    }
+
+    fn local_time_type_at(&self, t: UnixTimestamp) -> LocalTimeType<'_> {
+        let data = self.data.as_ref();
+        let block = &self.block;
+        match (self.transitions_until(t), &self.footer) {
+            (n, Some(footer)) if n == block.transition_count => LocalTimeType::Footer(footer),
+            (0, _) => LocalTimeType::Index(0),
+            (n, _) => LocalTimeType::Index(usize::from(data[block.transition_types + n - 1])),
+        }
+    }
+}
+
+fn changes_offset(transition: &Transition) -> bool {
+    transition.offset_before != transition.offset_after
+}
+
+/// For data compiled by `build.rs`, which is known to be valid, and its parsed footer.
+///
+/// This is the same as `Tzif::parse`, but in a `const` context so that it happens at compile time.
+/// That data always has a placeholder version 1 block with one local time type and one character.
+#[cfg(feature = "tzdb")]
+pub const fn from_compiled_data(data: &'static [u8], footer: Option<PosixTz>)
+                                -> Tzif<&'static [u8]> {
+    let start = HEADER_SIZE + LOCAL_TIME_TYPE_SIZE + 1;
+    let transition_count = read_u32(data, start + 32) as usize;
+    let local_time_type_count = read_u32(data, start + 36) as usize;
+    let char_count = read_u32(data, start + 40) as usize;
+    let transition_times = start + HEADER_SIZE;
+    let transition_types = transition_times + transition_count * 8;
+    let local_time_types = transition_types + transition_count;
+    let chars = local_time_types + local_time_type_count * LOCAL_TIME_TYPE_SIZE;
+    let block = Block {
+        time_size: 8,
+        transition_count,
+        transition_times,
+        transition_types,
+        local_time_type_count,
+        local_time_types,
+        chars,
+        char_count,
+    };
+    Tzif { data, block, footer }
+}
+
+impl<B: AsRef<[u8]>> TimeZone for Tzif<B> {
+    fn from_timestamp(&self, t: UnixTimestamp) -> NaiveDateTime {
+        self.offset_at(t).from_timestamp(t)
+    }
+
+    fn offset_at(&self, t: UnixTimestamp) -> FixedOffsetFromUtc {
+        match self.local_time_type_at(t) {
+            LocalTimeType::Index(i) => self.block.offset(self.data.as_ref(), i),
+            LocalTimeType::Footer(footer) => footer.offset_at(t),
+        }
+    }
+
+    fn abbreviation_at(&self, t: UnixTimestamp) -> Option<&str> {
+        match self.local_time_type_at(t) {
+            LocalTimeType::Index(i) => self.block.abbreviation(self.data.as_ref(), i),
+            LocalTimeType::Footer(footer) => footer.abbreviation_at(t),
+        }
+    }
+
+    fn is_dst_at(&self, t: UnixTimestamp) -> bool {
+        match self.local_time_type_at(t) {
+            LocalTimeType::Index(i) => self.block.is_dst(self.data.as_ref(), i),
+            LocalTimeType::Footer(footer) => footer.is_dst_at(t),
+        }
+    }
+
+    /// Transitions that only change the abbreviation or DST flag are skipped.
+    fn next_transition(&self, t: UnixTimestamp) -> Option<Transition> {
+        let block = &self.block;
+        (self.transitions_until(t)..block.transition_count)
+            .map(|i| self.table_transition(i))
+            .find(changes_offset)
+            .or_else(|| {
+                // The footer applies after the last transition.
+                let t = match block.transition_count {
+                    0 => t,
+                    n => cmp::max(t, self.table_transition(n - 1).at),
+                };
+                let at = self.footer.as_ref()?.next_dst_change(t)?;
+                Some(transition_at(self, at))
+            })
+    }
+
+    /// Transitions that only change the abbreviation or DST flag are skipped.
+    fn previous_transition(&self, t: UnixTimestamp) -> Option<Transition> {
+        let count = self.transitions_until(UnixTimestamp(t.0 - 1));
+        let from_footer = match (count, &self.footer) {
+            (n, Some(footer)) if n == self.block.transition_count => {
+                footer.previous_dst_change(t)
+                      .filter(|&at| n == 0 || at > self.table_transition(n - 1).at)
+                      .map(|at| transition_at(self, at))
+            }
+            _ => None,
+        };
+        from_footer.or_else(|| (0..count).rev().map(|i| self.table_transition(i))
+                                          .find(changes_offset))
+    }
+
+    fn to_timestamp(&self, d: &NaiveDateTime) -> Result<UnixTimestamp, LocalTimeConversionError> {
+        // Try every offset used in this time zone,
+        // and keep those that are in effect at the resulting instant.
+        let data = self.data.as_ref();
+        let block = &self.block;
+        let footer_offsets = self.footer.iter().flat_map(|footer| {
+            [footer.offset_outside_dst(), footer.offset_during_dst()]
+        });
+        let offsets = || (0..block.local_time_type_count).map(|i| block.offset(data, i))
+                                                         .chain(footer_offsets.clone());
+        let mut earlier = None;
+        let mut later = None;
+        for offset in offsets() {
+            let t = offset.to_unambiguous_timestamp(d);
+            if self.offset_at(t) == offset {
+                earlier = Some(earlier.map_or(t, |earlier| cmp::min(earlier, t)));
+                later = Some(later.map_or(t, |later| cmp::max(later, t)));
+            }
+        }
+        match (earlier, later) {
+            (Some(earlier), Some(later)) if earlier == later => Ok(earlier),
+            (Some(earlier), Some(later)) => {
+                Err(LocalTimeConversionError::Ambiguous { earlier, later })
+            }
+            _ => {
+                // Clocks jumped forward over this local time, somewhere between the instants
+                // for the largest and smallest offsets.
+                // Find that transition by bisection: before it, the local time is before `d`.
+                let local = Utc.to_unambiguous_timestamp(d).0;
+                let is_after_d = |t: i64| t + i64::from(self.offset_at(UnixTimestamp(t))
+                                                            .seconds_ahead_of_utc()) > local;
+                let seconds = offsets().map(|offset| i64::from(offset.seconds_ahead_of_utc()));
+                let low = local - seconds.clone().max().unwrap();
+                let high = local - seconds.min().unwrap();
+                let transition = first_instant_after(low, high, is_after_d);
+                Err(LocalTimeConversionError::Nonexistent {
+                    before: self.offset_at(UnixTimestamp(transition))
+                                .to_unambiguous_timestamp(d),
+                    after: self.offset_at(UnixTimestamp(transition - 1))
+                               .to_unambiguous_timestamp(d),
+                    transition: UnixTimestamp(transition),
+                })
+            }
+        }
+    }
+}
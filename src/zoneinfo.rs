@@ -0,0 +1,133 @@
+//! Loading time zones from the system’s copy of the IANA time zone database.
+
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+use super::{Tzif, TzifError, PosixTz, DaylightSaving};
+
+/// Returned when loading a time zone fails.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ZoneinfoError {
+    /// The time zone name is empty or absolute, has empty, `.` or `..` components,
+    /// or has characters other than ASCII letters, digits, `-`, `+`, `_` and `.`.
+    InvalidName,
+
+    /// Reading the file failed. `NotFound` typically means that there is no such time zone.
+    Io(io::ErrorKind),
+
+    /// The file does not contain valid TZif data.
+    Tzif(TzifError),
+}
+
+impl From<io::Error> for ZoneinfoError {
+    fn from(error: io::Error) -> Self {
+        ZoneinfoError::Io(error.kind())
+    }
+}
+
+impl From<TzifError> for ZoneinfoError {
+    fn from(error: TzifError) -> Self {
+        ZoneinfoError::Tzif(error)
+    }
+}
+
+impl Tzif<Vec<u8>> {
+    /// Read and parse a TZif file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ZoneinfoError> {
+        Ok(Tzif::parse(fs::read(path)?)?)
+    }
+
+    /// Load a time zone by its IANA name, such as `Europe/Paris`,
+    /// from the directory in the `TZDIR` environment variable if set,
+    /// or from `/usr/share/zoneinfo`.
+    ///
+    /// ```rust,no_run
+    /// use gregor::{Tzif, DateTime, UnixTimestamp};
+    /// let paris = Tzif::from_zoneinfo("Europe/Paris").unwrap();
+    /// let d = DateTime::from_timestamp(UnixTimestamp(1468702726), paris);
+    /// assert_eq!(d.hour(), 22);
+    /// ```
+    pub fn from_zoneinfo(name: &str) -> Result<Self, ZoneinfoError> {
+        zoneinfo_in(&zoneinfo_directory(), name)
+    }
+
+    /// The system’s local time zone.
+    ///
+    /// This is `/etc/localtime` if the `TZ` environment variable is not set.
+    /// Otherwise, the value of `TZ` (with any leading `:` removed) is either
+    /// an absolute file name, an IANA time zone name for `from_zoneinfo`,
+    /// or a POSIX `TZ` string such as `EST5EDT,M3.2.0,M11.1.0`.
+    /// An empty `TZ` is UTC.
+    pub fn local() -> Result<Self, ZoneinfoError> {
+        local_from(env::var_os("TZ").as_deref(), &zoneinfo_directory())
+    }
+}
+
+fn zoneinfo_directory() -> PathBuf {
+    match env::var_os("TZDIR") {
+        Some(ref directory) if !directory.is_empty() => PathBuf::from(directory),
+        _ => PathBuf::from("/usr/share/zoneinfo"),
+    }
+}
+
+/// `Tzif::from_zoneinfo`, with the database in `directory`.
+pub fn zoneinfo_in(directory: &Path, name: &str) -> Result<Tzif<Vec<u8>>, ZoneinfoError> {
+    let valid = |component: &str| {
+        component != "." && component != ".." && !component.is_empty() &&
+        component.bytes().all(|b| matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+                                              b'-' | b'+' | b'_' | b'.'))
+    };
+    if !name.split('/').all(valid) {
+        return Err(ZoneinfoError::InvalidName)
+    }
+    Tzif::from_file(directory.join(name))
+}
+
+/// `Tzif::local`, with this value of the `TZ` environment variable
+/// and the database in `directory`.
+pub fn local_from(tz: Option<&OsStr>, directory: &Path)
+                  -> Result<Tzif<Vec<u8>>, ZoneinfoError> {
+    let tz = match tz {
+        Some(tz) => tz,
+        None => return Tzif::from_file("/etc/localtime"),
+    };
+    let tz = tz.to_str().ok_or(ZoneinfoError::InvalidName)?;
+    if tz.is_empty() {
+        return Ok(from_posix_tz("UTC0").unwrap())
+    }
+    let name = tz.trim_start_matches(':');
+    if name.starts_with('/') {
+        return Tzif::from_file(name)
+    }
+    match zoneinfo_in(directory, name) {
+        Err(ZoneinfoError::InvalidName) | Err(ZoneinfoError::Io(io::ErrorKind::NotFound))
+        if !tz.starts_with(':') => {
+            from_posix_tz(tz).ok_or(ZoneinfoError::InvalidName)
+        }
+        result => result,
+    }
+}
+
+/// Make TZif data with no transitions and this POSIX `TZ` string as the footer.
+fn from_posix_tz(tz: &str) -> Option<Tzif<Vec<u8>>> {
+    let offset = tz.parse::<PosixTz>().ok()?.offset_outside_dst().seconds_ahead_of_utc() as u32;
+    let mut data = Vec::new();
+    for _ in 0..2 {
+        data.extend_from_slice(b"TZif2");
+        data.extend_from_slice(&[0; 15]);
+        // Counts: UT and standard indicators, leap seconds, transitions, types, and characters
+        for &count in &[0_u32, 0, 0, 0, 1, 1] {
+            data.extend_from_slice(&count.to_be_bytes())
+        }
+        // One local time type, not DST, with an empty abbreviation
+        data.extend_from_slice(&offset.to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0]);
+    }
+    data.push(b'\n');
+    data.extend_from_slice(tz.as_bytes());
+    data.push(b'\n');
+    Tzif::parse(data).ok()
+}